@@ -1,8 +1,18 @@
 use std::path::Path;
 
-use crate::{YamlShape, YamlVerifyError, YamlCorrectness, categories::structs::Category, deploy::structs::DeployOptions, files::structs::File};
+use crate::hints::Hint;
+use crate::lists::structs::Author;
+use crate::{YamlShape, YamlVerifyError, YamlVerifyWarning, YamlCorrectness, AdminBot, categories::structs::Category, deploy::structs::DeployOptions, files::structs::File};
 
 use crate::files::structs::Files;
+use crate::deploy::compose::{ComposeFile, generate_compose};
+use crate::deploy::k8s::{K8sConfig, K8sManifest, generate_manifests};
+use crate::deploy::links::{HostConfig, deploy_links};
+use crate::deploy::structs::{DeployLink, StaticSite};
+use crate::files::structs::ContainerType;
+use crate::template::{substitute, served_file, Variable};
+use crate::markdown::render_html;
+use crate::correctness::MarkdownCorrectness;
 
 impl YamlShape {
     pub fn try_from_str(s: &str, correctness: &YamlCorrectness, base_path: Option<&Path>) -> Result<YamlShape, YamlVerifyError> {
@@ -24,9 +34,12 @@ impl YamlShape {
     pub fn author_iter(&self) -> impl Iterator<Item = &str> {
         self.authors.iter()
     }
-    pub fn authors(&self) -> &[String] {
+    pub fn authors(&self) -> &[Author] {
         self.authors.slice()
     }
+    pub fn author_handles(&self) -> impl Iterator<Item = &str> {
+        self.authors.slice().iter().map(Author::handle)
+    }
 
     pub fn category_str_iter(&self) -> impl Iterator<Item = &str> {
         self.categories.iter().map(Category::as_str)
@@ -38,16 +51,90 @@ impl YamlShape {
         self.categories.slice()
     }
 
+    /// The first category, or `category` if it was given on its own.
+    pub fn primary_category(&self) -> Option<&Category> {
+        self.categories.primary()
+    }
+
+    pub fn tag_iter(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter()
+    }
+    pub fn tags(&self) -> &[String] {
+        self.tags.slice()
+    }
+
     pub fn hint_iter(&self) -> impl Iterator<Item = &str> {
-        self.hints.iter()
+        self.hints.iter().map(|hint| hint.text.as_str())
     }
-    pub fn hints(&self) -> &[String] {
+    pub fn hints(&self) -> &[Hint] {
         self.hints.slice()
     }
 
     pub fn deploy(&self) -> Option<&DeployOptions> {
         self.deploy.as_ref()
     }
+
+    pub fn static_site(&self) -> Option<StaticSite<'_>> {
+        let target = self.deploy.as_ref()?.static_site.as_ref()?;
+        let files = self
+            .file_iter()
+            .into_iter()
+            .flatten()
+            .filter(|file| file.container() == Some(ContainerType::Static))
+            .collect();
+        Some(StaticSite { target, files })
+    }
+
+    pub fn admin_bot(&self) -> Option<&AdminBot> {
+        self.admin_bot.as_ref()
+    }
+
+    pub fn compose(&self) -> Option<ComposeFile> {
+        self.deploy.as_ref().map(|deploy| generate_compose(&self.name, deploy))
+    }
+
+    pub fn k8s_manifests(&self, config: &K8sConfig) -> Option<Vec<K8sManifest>> {
+        self.deploy.as_ref().map(|deploy| generate_manifests(&self.name, deploy, config))
+    }
+
+    pub fn deploy_links(&self, config: &HostConfig) -> Vec<DeployLink> {
+        self.deploy.as_ref().map_or_else(Vec::new, |deploy| deploy_links(&self.name, deploy, config))
+    }
+
+    fn render_template(&self, text: &str, links: &[DeployLink], config: &HostConfig) -> String {
+        substitute(text, |placeholder| match placeholder.variable().ok()? {
+            Variable::Name => Some(self.name.clone()),
+            Variable::Points => Some(self.points.to_string()),
+            Variable::File(name) => served_file(self.files(), name)
+                .map(|file| config.file_link(&self.name, &file.download_name())),
+            Variable::Link(target_type) => links
+                .iter()
+                .find(|link| link.deploy_target == target_type)
+                .map(|link| link.link.clone()),
+        })
+    }
+
+    /// The description with its variables (`{{ name }}`, `{{ points }}`,
+    /// `{{ file:<name> }}` and `{{ link:<target> }}`) filled in, and escaped
+    /// braces (`{{{{` / `}}}}`) unescaped.
+    pub fn render_description(&self, config: &HostConfig) -> String {
+        self.render_template(self.description.as_str(), &self.deploy_links(config), config)
+    }
+
+    /// The text of each hint, with its variables filled in.
+    pub fn render_hints(&self, config: &HostConfig) -> Vec<String> {
+        let links = self.deploy_links(config);
+        self.hints
+            .iter()
+            .map(|hint| self.render_template(&hint.text, &links, config))
+            .collect()
+    }
+
+    /// [`Self::render_description`] as sanitized HTML, keeping only the raw
+    /// HTML tags `markdown` allows.
+    pub fn render_description_html(&self, config: &HostConfig, markdown: &MarkdownCorrectness) -> String {
+        render_html(&self.render_description(config), markdown.allowed_html())
+    }
 }
 
 impl YamlShape {
@@ -61,10 +148,14 @@ impl YamlShape {
 
 impl YamlShape {
     pub fn chall_name(&self) -> &str { &self.name }
-    pub fn description(&self) -> &str { &self.description }
+    pub fn description(&self) -> &str { self.description.as_str() }
+    pub fn description_path(&self) -> Option<&Path> { self.description.path() }
     
     pub fn points(&self) -> u64 { self.points }
+    pub fn difficulty(&self) -> Option<&str> { self.difficulty.as_deref() }
 
     pub fn visible(&self) -> bool { self.visible }
+
+    pub fn warnings(&self) -> &[YamlVerifyWarning] { &self.warnings }
 }
 
@@ -2,10 +2,11 @@ pub mod structs;
 
 use std::fmt::Display;
 
-pub use structs::Categories;
+pub use structs::{Categories, CategoryDef, DEFAULT_CATEGORY_DEFS};
 
 use serde_yaml::Value as YamlValue;
 
+use crate::correctness::CategoryCorrectness;
 use crate::structs::{get_type, ValueType};
 
 
@@ -13,12 +14,13 @@ use crate::structs::{get_type, ValueType};
 pub enum CategoryError {
     InvalidCategories(Vec<String>, Vec<ValueType>),
     InvalidBaseType(ValueType),
+    PrimaryNotString(ValueType),
     #[default]
     MissingKey,
 }
 impl Display for CategoryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use CategoryError::{InvalidBaseType, InvalidCategories, MissingKey};
+        use CategoryError::{InvalidBaseType, InvalidCategories, MissingKey, PrimaryNotString};
         match self {
             InvalidCategories(names, types) => {
                 if !names.is_empty() {
@@ -33,13 +35,35 @@ impl Display for CategoryError {
                 }
             }
             InvalidBaseType(t) => write!(f, "Categories should be a list, not {t}.")?,
+            PrimaryNotString(t) => write!(f, "The category should be a string, not {t}.")?,
             MissingKey => write!(f, "You have to define `categories`.")?,
         }
         Ok(())
     }
 }
 
-pub fn value_to_categories(value: &YamlValue) -> Result<Categories, CategoryError> {
+/// Reads the categories a competition defines from YAML: a list of maps, each
+/// with an `id` and a `name`, and optionally `aliases`, a `color` and a
+/// `description`.
+pub fn parse_category_defs(yaml_text: &str) -> Result<Vec<CategoryDef>, serde_yaml::Error> {
+    serde_yaml::from_str(yaml_text)
+}
+
+/// `category: <primary>` with any extra `categories` listed after it.
+pub fn primary_to_categories(primary: &YamlValue, rest: Option<&YamlValue>, correctness: &CategoryCorrectness) -> Result<Categories, CategoryError> {
+    if !primary.is_string() {
+        return Err(CategoryError::PrimaryNotString(get_type(primary)));
+    }
+
+    let mut names = vec![primary.clone()];
+    if let Some(rest) = rest {
+        let sequence = rest.as_sequence().ok_or_else(|| CategoryError::InvalidBaseType(get_type(rest)))?;
+        names.extend(sequence.iter().cloned());
+    }
+    value_to_categories(&YamlValue::Sequence(names), correctness)
+}
+
+pub fn value_to_categories(value: &YamlValue, correctness: &CategoryCorrectness) -> Result<Categories, CategoryError> {
     use CategoryError::{InvalidBaseType, InvalidCategories};
 
     if !value.is_sequence() {
@@ -58,7 +82,7 @@ pub fn value_to_categories(value: &YamlValue) -> Result<Categories, CategoryErro
             }
         );
 
-        match Categories::try_new(cand_name) {
+        match Categories::try_new(cand_name, correctness) {
             Ok(categories) => if bad_type.is_empty() {
                 Ok(categories)
             } else {
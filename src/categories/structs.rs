@@ -1,32 +1,89 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 
-#[allow(unused)]
-static CATEGORY_DEFAULTS: [&str; 5] = ["misc", "binex", "foren", "crypto", "webex"];
+use serde::Deserialize;
 
-use lazy_static::lazy_static;
-use std::collections::HashSet;
-lazy_static!{
-    static ref CATEGORY_RAW_ENV_VAR: Option<String> = std::env::var("CATEGORIES").ok();
+use crate::correctness::CategoryCorrectness;
 
-    pub static ref CATEGORIES: HashSet<String> = CATEGORY_RAW_ENV_VAR.as_ref().map_or_else(
-        || CATEGORY_DEFAULTS.into_iter().map(str::to_string).collect(),
-        |val| val.split(',').map(|part| part.trim().to_string()).collect(),
-    );
+/// The categories used when a competition doesn't define its own.
+pub const DEFAULT_CATEGORY_DEFS: &[CategoryDef] = &[
+    CategoryDef::builtin("misc", "Miscellaneous", &[]),
+    CategoryDef::builtin("binex", "Binary Exploitation", &[Cow::Borrowed("pwn")]),
+    CategoryDef::builtin("foren", "Forensics", &[Cow::Borrowed("forensics")]),
+    CategoryDef::builtin("crypto", "Cryptography", &[]),
+    CategoryDef::builtin("webex", "Web Exploitation", &[Cow::Borrowed("web")]),
+    CategoryDef::builtin("rev", "Reverse Engineering", &[Cow::Borrowed("reversing")]),
+];
+
+/// A category as the competition defines it. Challenges can use its `id` or
+/// any of its `aliases`, but always end up with the `id`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CategoryDef {
+    pub id: Cow<'static, str>,
+    pub name: Cow<'static, str>,
+    #[serde(default)]
+    pub aliases: Cow<'static, [Cow<'static, str>]>,
+    #[serde(default)]
+    pub color: Option<Cow<'static, str>>,
+    #[serde(default)]
+    pub description: Option<Cow<'static, str>>,
+}
+
+impl CategoryDef {
+    pub fn new(id: impl Into<Cow<'static, str>>, name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            aliases: Cow::Borrowed(&[]),
+            color: None,
+            description: None,
+        }
+    }
+    pub const fn builtin(id: &'static str, name: &'static str, aliases: &'static [Cow<'static, str>]) -> Self {
+        Self {
+            id: Cow::Borrowed(id),
+            name: Cow::Borrowed(name),
+            aliases: Cow::Borrowed(aliases),
+            color: None,
+            description: None,
+        }
+    }
+    pub fn with_aliases(self, aliases: impl Into<Cow<'static, [Cow<'static, str>]>>) -> Self { Self { aliases: aliases.into(), ..self } }
+    pub fn with_color(self, color: impl Into<Cow<'static, str>>) -> Self { Self { color: Some(color.into()), ..self } }
+    pub fn with_description(self, description: impl Into<Cow<'static, str>>) -> Self { Self { description: Some(description.into()), ..self } }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.id.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
 }
 
 #[derive(PartialEq)]
 pub struct Category {
     name: String,
+    def: Option<CategoryDef>,
 }
 
 impl Category {
-    pub fn try_new(name: &'_ str) -> Option<Self> {
-        // CATEGORIES.get(name).map(|name| Self { name })
-        Some(Self { name: name.to_string() })
+    /// Resolves `name` against the defined categories, if there are any.
+    pub fn try_new(name: &'_ str, correctness: &CategoryCorrectness) -> Option<Self> {
+        if let CategoryCorrectness::Defined(defs) = correctness {
+            defs.iter()
+                .find(|def| def.matches(name))
+                .map(|def| Self { name: def.id.to_string(), def: Some(def.clone()) })
+        } else {
+            Some(Self { name: name.to_string(), def: None })
+        }
     }
     pub fn as_str(&self) -> &str {
         &self.name
     }
+    pub fn def(&self) -> Option<&CategoryDef> {
+        self.def.as_ref()
+    }
+    pub fn display_name(&self) -> &str {
+        self.def.as_ref().map_or(&self.name, |def| &def.name)
+    }
 }
 
 impl Debug for Category {
@@ -39,13 +96,16 @@ impl Debug for Category {
 pub struct Categories(Vec<Category>);
 
 impl Categories {
-    pub fn try_new<'a>(category_names: impl IntoIterator<Item = &'a str>) -> Result<Categories, Vec<&'a str>> {
-        let mut good_cats = vec![];
+    pub fn try_new<'a>(category_names: impl IntoIterator<Item = &'a str>, correctness: &CategoryCorrectness) -> Result<Categories, Vec<&'a str>> {
+        let mut good_cats: Vec<Category> = vec![];
         let mut invalid_cat_names = vec![];
 
         for name in category_names {
-            if let Some(cat) = Category::try_new(name) {
-                good_cats.push(cat);
+            if let Some(cat) = Category::try_new(name, correctness) {
+                // An alias and its category both being listed is still one category
+                if !good_cats.iter().any(|good| good.name == cat.name) {
+                    good_cats.push(cat);
+                }
             } else {
                 invalid_cat_names.push(name);
             }
@@ -58,6 +118,11 @@ impl Categories {
         }
     }
 
+    /// The category a challenge is listed under, which is the first one.
+    pub fn primary(&self) -> Option<&Category> {
+        self.0.first()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Category> {
         self.0.iter()
     }
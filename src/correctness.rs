@@ -1,10 +1,12 @@
 use std::borrow::Cow;
 use std::fmt::{Debug, Display};
+use std::path::PathBuf;
 use std::sync::Arc;
 use regex::Regex;
 
 use crate::YamlShape;
-use crate::categories::structs::Category;
+use crate::files::fetch::SharedFetcher;
+use crate::categories::structs::{Category, CategoryDef, DEFAULT_CATEGORY_DEFS};
 
 
 #[derive(Debug, Clone, PartialEq)]
@@ -12,6 +14,20 @@ pub struct YamlCorrectness {
     flag: FlagCorrectness,
     categories: CategoryCorrectness,
     points: PointCorrectness,
+    /// Boxed so that a `YamlCorrectness` stays small enough to be returned as
+    /// an error.
+    rest: Box<RestCorrectness>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RestCorrectness {
+    tags: TagCorrectness,
+    difficulty: DifficultyCorrectness,
+    authors: AuthorCorrectness,
+    required: RequiredKeys,
+    markdown: MarkdownCorrectness,
+    base_images: BaseImageCorrectness,
+    files: FilePolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -38,9 +54,108 @@ pub enum CategoryCorrectness {
     List {
         names: Cow<'static, [Cow<'static, str>]>,
         requires_case_match: bool,
+    },
+    Defined(Cow<'static, [CategoryDef]>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagCorrectness {
+    AnyTag,
+    List(Cow<'static, [Cow<'static, str>]>),
+}
+
+/// Known author handles, so that typos in `authors` get caught.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthorCorrectness {
+    AnyAuthor,
+    Registry(Cow<'static, [Cow<'static, str>]>),
+}
+
+/// Whether descriptions are checked as Markdown, and which raw HTML tags
+/// they may use.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownCorrectness {
+    Unchecked,
+    Checked { allowed_html: Cow<'static, [Cow<'static, str>]> },
+}
+impl MarkdownCorrectness {
+    pub fn allowed_html(&self) -> &[Cow<'static, str>] {
+        match self {
+            Self::Unchecked => &[],
+            Self::Checked { allowed_html } => allowed_html,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRequirement { Required, Optional }
+
+/// Whether `hints`, `authors`, `files` and `deploy` have to be written out.
+/// Optional keys that are left out get their empty default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequiredKeys {
+    pub hints: KeyRequirement,
+    pub authors: KeyRequirement,
+    pub files: KeyRequirement,
+    pub deploy: KeyRequirement,
+}
+impl RequiredKeys {
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut KeyRequirement> {
+        match key {
+            "hints" => Some(&mut self.hints),
+            "authors" => Some(&mut self.authors),
+            "files" => Some(&mut self.files),
+            "deploy" => Some(&mut self.deploy),
+            _ => None,
+        }
+    }
+}
+impl Default for RequiredKeys {
+    fn default() -> Self {
+        Self {
+            hints: KeyRequirement::Optional,
+            authors: KeyRequirement::Required,
+            files: KeyRequirement::Optional,
+            deploy: KeyRequirement::Optional,
+        }
     }
 }
 
+/// A difficulty a competition allows, and the points challenges at that
+/// difficulty may be worth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyLevel {
+    pub name: Cow<'static, str>,
+    pub min_points: Option<u64>,
+    pub max_points: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DifficultyCorrectness {
+    AnyLevel,
+    Levels(Cow<'static, [DifficultyLevel]>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaseImageCorrectness {
+    AnyImage,
+    List(Cow<'static, [Cow<'static, str>]>),
+}
+
+/// Limits on the files a challenge refers to. Types are matched against a
+/// file's extension and the type its magic bytes say it is (see
+/// `files::file_type`). Paths must stay inside the challenge directory unless
+/// they're in one of the `shared_roots`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FilePolicy {
+    pub max_file_size: Option<u64>,
+    pub max_total_size: Option<u64>,
+    pub allowed_types: Option<Cow<'static, [Cow<'static, str>]>>,
+    pub denied_types: Cow<'static, [Cow<'static, str>]>,
+    pub shared_roots: Cow<'static, [PathBuf]>,
+    pub fetcher: Option<SharedFetcher>,
+}
+
 pub trait CanBePred: Fn(u64) -> bool + Debug + Send + Sync {}
 
 #[derive(Clone)]
@@ -79,17 +194,27 @@ impl YamlCorrectness {
     pub fn check_cats<'a>(&self, categories: impl Iterator<Item = &'a str>) -> bool { self.categories.check(categories) }
     pub fn check_pnts(&self, points: u64) -> bool { self.points.check(points) }
 
+    pub fn check_tags<'a>(&self, tags: impl Iterator<Item = &'a str>) -> bool { self.rest.tags.check(tags) }
+    pub fn check_difficulty(&self, difficulty: Option<&str>, points: u64) -> bool { self.rest.difficulty.check(difficulty, points) }
+
     pub fn verify<'a>(&self, shape: &'a YamlShape) -> Result<&'a YamlShape, YamlCorrectness> {
         let flag_ok = self.check_flag(shape.flag.as_str());
         let cats_ok = self.check_cats(shape.categories.iter().map(Category::as_str));
         let pnts_ok = self.check_pnts(shape.points);
-        if flag_ok && cats_ok && pnts_ok {
+        let tags_ok = self.check_tags(shape.tags.iter());
+        let diff_ok = self.check_difficulty(shape.difficulty.as_deref(), shape.points);
+        if flag_ok && cats_ok && pnts_ok && tags_ok && diff_ok {
             Ok(shape)
         } else {
             Err(Self {
                 flag: if flag_ok { FlagCorrectness::None } else { self.flag.clone() },
                 categories: if cats_ok { CategoryCorrectness::AnyStr } else { self.categories.clone() },
                 points: if pnts_ok { PointCorrectness::None } else { self.points.clone() },
+                rest: Box::new(RestCorrectness {
+                    tags: if tags_ok { TagCorrectness::AnyTag } else { self.rest.tags.clone() },
+                    difficulty: if diff_ok { DifficultyCorrectness::AnyLevel } else { self.rest.difficulty.clone() },
+                    ..RestCorrectness::default()
+                }),
             })
         }
     }
@@ -112,6 +237,10 @@ impl FlagCorrectness {
 }
 
 impl CategoryCorrectness {
+    pub fn defaults() -> Self {
+        Self::Defined(Cow::Borrowed(DEFAULT_CATEGORY_DEFS))
+    }
+
     pub fn check<'a>(&self, mut categories: impl Iterator<Item = &'a str>) -> bool {
         match self {
             Self::AnyStr => true,
@@ -128,6 +257,89 @@ impl CategoryCorrectness {
 
                 categories.all(pred)
             },
+            // Anything undefined was already rejected while parsing
+            Self::Defined(defs) => categories.all(|name| defs.iter().any(|def| def.id == name)),
+        }
+    }
+}
+
+impl BaseImageCorrectness {
+    /// Entries without a tag or digest allow every tag of that image, while
+    /// entries with one only allow that exact reference.
+    pub fn check(&self, image: &str) -> bool {
+        fn repository(image: &str) -> &str {
+            let image = image.split_once('@').map_or(image, |(repo, _)| repo);
+            let name_start = image.rfind('/').map_or(0, |idx| idx + 1);
+            match image[name_start..].find(':') {
+                Some(tag_start) => &image[..name_start + tag_start],
+                None => image,
+            }
+        }
+
+        match self {
+            Self::AnyImage => true,
+            Self::List(images) => image == "scratch" || images.iter().any(
+                |allowed| if repository(allowed) == allowed.as_ref() {
+                    repository(image) == allowed.as_ref()
+                } else {
+                    image == allowed.as_ref()
+                }
+            ),
+        }
+    }
+}
+
+impl FilePolicy {
+    /// The first of `types` that isn't allowed, if any. With an allow-list,
+    /// every type (the extension and the detected one) has to be on it.
+    pub fn disallowed_type<'a>(&self, types: &'a [String]) -> Option<&'a str> {
+        let denied = types
+            .iter()
+            .find(|ty| self.denied_types.iter().any(|denied| denied.eq_ignore_ascii_case(ty)));
+        if let Some(denied) = denied {
+            return Some(denied);
+        }
+
+        let allowed = self.allowed_types.as_ref()?;
+        if types.is_empty() {
+            return Some("<no extension>");
+        }
+        types
+            .iter()
+            .find(|ty| !allowed.iter().any(|allowed| allowed.eq_ignore_ascii_case(ty)))
+            .map(String::as_str)
+    }
+}
+
+impl TagCorrectness {
+    pub fn check<'a>(&self, mut tags: impl Iterator<Item = &'a str>) -> bool {
+        match self {
+            Self::AnyTag => true,
+            Self::List(vocabulary) => tags.all(|tag| vocabulary.iter().any(|known| known.eq_ignore_ascii_case(tag))),
+        }
+    }
+}
+
+impl DifficultyLevel {
+    pub fn new(name: impl Into<Cow<'static, str>>, min_points: Option<u64>, max_points: Option<u64>) -> Self {
+        Self { name: name.into(), min_points, max_points }
+    }
+
+    pub fn allows(&self, points: u64) -> bool {
+        self.min_points.is_none_or(|min| points >= min) && self.max_points.is_none_or(|max| points <= max)
+    }
+}
+
+impl DifficultyCorrectness {
+    /// Challenges without a difficulty are always fine; ones with a difficulty
+    /// need it to be a known level with their points in its range.
+    pub fn check(&self, difficulty: Option<&str>, points: u64) -> bool {
+        match (self, difficulty) {
+            (Self::AnyLevel, _) | (_, None) => true,
+            (Self::Levels(levels), Some(difficulty)) => levels
+                .iter()
+                .find(|level| level.name.eq_ignore_ascii_case(difficulty))
+                .is_some_and(|level| level.allows(points)),
         }
     }
 }
@@ -136,7 +348,7 @@ impl PointCorrectness {
     pub fn check(&self, num: u64) -> bool {
         match self {
             Self::None => true,
-            Self::Multiple(factor) => num % factor == 0,
+            Self::Multiple(factor) => num.is_multiple_of(*factor),
             Self::Pred(pred) => pred(num),
         }
     }
@@ -148,6 +360,20 @@ impl Default for YamlCorrectness {
             flag: FlagCorrectness::None,
             categories: CategoryCorrectness::AnyStr,
             points: PointCorrectness::None,
+            rest: Box::default(),
+        }
+    }
+}
+impl Default for RestCorrectness {
+    fn default() -> Self {
+        Self {
+            tags: TagCorrectness::AnyTag,
+            difficulty: DifficultyCorrectness::AnyLevel,
+            authors: AuthorCorrectness::AnyAuthor,
+            required: RequiredKeys::default(),
+            markdown: MarkdownCorrectness::Unchecked,
+            base_images: BaseImageCorrectness::AnyImage,
+            files: FilePolicy::default(),
         }
     }
 }
@@ -157,6 +383,22 @@ impl YamlCorrectness {
     pub fn with_flag(self, flag: FlagCorrectness) -> Self { Self { flag, ..self } }
     pub fn with_cats(self, categories: CategoryCorrectness) -> Self { Self { categories, ..self } }
     pub fn with_pnts(self, points: PointCorrectness) -> Self { Self { points, ..self } }
+    pub fn with_authors(mut self, authors: AuthorCorrectness) -> Self { self.rest.authors = authors; self }
+    pub fn with_difficulty(mut self, difficulty: DifficultyCorrectness) -> Self { self.rest.difficulty = difficulty; self }
+    pub fn with_required(mut self, required: RequiredKeys) -> Self { self.rest.required = required; self }
+    pub fn with_markdown(mut self, markdown: MarkdownCorrectness) -> Self { self.rest.markdown = markdown; self }
+    pub fn with_tags(mut self, tags: TagCorrectness) -> Self { self.rest.tags = tags; self }
+    pub fn with_images(mut self, base_images: BaseImageCorrectness) -> Self { self.rest.base_images = base_images; self }
+    pub fn with_files(mut self, files: FilePolicy) -> Self { self.rest.files = files; self }
+}
+
+impl YamlCorrectness {
+    pub fn categories(&self) -> &CategoryCorrectness { &self.categories }
+    pub fn authors(&self) -> &AuthorCorrectness { &self.rest.authors }
+    pub fn required(&self) -> &RequiredKeys { &self.rest.required }
+    pub fn markdown(&self) -> &MarkdownCorrectness { &self.rest.markdown }
+    pub fn base_images(&self) -> &BaseImageCorrectness { &self.rest.base_images }
+    pub fn file_policy(&self) -> &FilePolicy { &self.rest.files }
 }
 
 
@@ -175,6 +417,14 @@ impl YamlCorrectness {
             write!(f, "    ")?;
             self.points.show_issue(f)?;
         }
+        if !matches!(self.rest.difficulty, DifficultyCorrectness::AnyLevel) {
+            write!(f, "    ")?;
+            self.rest.difficulty.show_issue(f)?;
+        }
+        if !matches!(self.rest.tags, TagCorrectness::AnyTag) {
+            write!(f, "    ")?;
+            self.rest.tags.show_issue(f)?;
+        }
 
         Ok(())
     }
@@ -205,6 +455,40 @@ impl CategoryCorrectness {
                     writeln!(f, "insensitive)")
                 }
             },
+            Defined(defs) => {
+                let ids: Vec<_> = defs.iter().map(|def| def.id.as_ref()).collect();
+                writeln!(f, "The categories should be one of {ids:?} (or one of their aliases)")
+            },
+        }
+    }
+}
+impl DifficultyCorrectness {
+    pub fn show_issue(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DifficultyCorrectness::*;
+        match self {
+            AnyLevel => Ok(()),
+            Levels(levels) => {
+                write!(f, "The difficulty should be one of")?;
+                for (idx, level) in levels.iter().enumerate() {
+                    let sep = if idx == 0 { "" } else { "," };
+                    match (level.min_points, level.max_points) {
+                        (Some(min), Some(max)) => write!(f, "{sep} `{}` ({min} to {max} points)", level.name)?,
+                        (Some(min), None) => write!(f, "{sep} `{}` (at least {min} points)", level.name)?,
+                        (None, Some(max)) => write!(f, "{sep} `{}` (at most {max} points)", level.name)?,
+                        (None, None) => write!(f, "{sep} `{}`", level.name)?,
+                    }
+                }
+                writeln!(f)
+            },
+        }
+    }
+}
+impl TagCorrectness {
+    pub fn show_issue(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TagCorrectness::*;
+        match self {
+            AnyTag => Ok(()),
+            List(vocabulary) => writeln!(f, "The tags should be from {vocabulary:?} (case insensitive)"),
         }
     }
 }
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use regex::Regex;
+use serde_yaml::{Mapping as YamlMapping, Value as YamlValue};
+
+use crate::flag::Flag;
+use crate::structs::{get_type, ValueType};
+use crate::template::substitute;
+use crate::Flop;
+
+use super::error::{AdminBotError, AllowedUrlsError, CookieError, VisitsError};
+use super::structs::{DeployOptions, DeployTargetType};
+
+
+const DEFAULT_VISIT_TIMEOUT: u64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct AdminBot {
+    pub visits: DeployTargetType,
+    pub cookie: Option<AdminCookie>,
+    pub timeout: Duration,
+    pub allowed_urls: Vec<Regex>,
+}
+impl PartialEq for AdminBot {
+    fn eq(&self, other: &Self) -> bool {
+        self.visits == other.visits
+            && self.cookie == other.cookie
+            && self.timeout == other.timeout
+            && self.allowed_urls.iter().map(Regex::as_str).eq(other.allowed_urls.iter().map(Regex::as_str))
+    }
+}
+impl AdminBot {
+    pub fn allows_url(&self, url: &str) -> bool {
+        self.allowed_urls.is_empty() || self.allowed_urls.iter().any(|regex| regex.is_match(url))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+}
+
+
+fn parse_visits(mapping: &YamlMapping) -> Result<DeployTargetType, VisitsError> {
+    let value = mapping.get("visits").ok_or(VisitsError::Missing)?;
+    let name = value.as_str().ok_or_else(|| VisitsError::NotStr(get_type(value)))?;
+    match DeployTargetType::try_from_str(name) {
+        Some(target @ DeployTargetType::Web) => Ok(target),
+        _ => Err(VisitsError::NotWeb(name.to_string())),
+    }
+}
+
+fn parse_cookie(value: &YamlValue, flag: &Flag) -> Result<AdminCookie, CookieError> {
+    let mapping = value.as_mapping().ok_or_else(|| CookieError::BadBaseType(get_type(value)))?;
+
+    let get_str = |key: &'static str| mapping.get(key).map(
+        |value| value.as_str().map(str::to_string).ok_or(CookieError::BadField(key, get_type(value)))
+    ).flop();
+
+    let name = get_str("name")?.ok_or(CookieError::BadField("name", ValueType::NULL))?;
+    let value = get_str("value")?.ok_or(CookieError::BadField("value", ValueType::NULL))?;
+    let domain = get_str("domain")?;
+
+    let value = substitute(&value, |placeholder| (placeholder.key == "flag").then(|| flag.as_str().to_string()));
+
+    Ok(AdminCookie { name, value, domain })
+}
+
+fn parse_allowed_urls(value: &YamlValue) -> Result<Vec<Regex>, AllowedUrlsError> {
+    let sequence = value.as_sequence().ok_or_else(|| AllowedUrlsError::BadType(get_type(value)))?;
+
+    sequence
+        .iter()
+        .map(|entry| {
+            let pattern = entry.as_str().ok_or_else(|| AllowedUrlsError::BadEntryType(get_type(entry)))?;
+            Regex::new(pattern).map_err(|_| AllowedUrlsError::BadRegex(pattern.to_string()))
+        })
+        .collect()
+}
+
+pub fn parse_admin_bot(value: &YamlValue, flag: &Flag) -> Result<AdminBot, AdminBotError> {
+    let mapping = value.as_mapping().ok_or_else(|| AdminBotError::BadBaseType(get_type(value)))?;
+
+    let visits = parse_visits(mapping);
+    let cookie = mapping.get("cookie").map(|value| parse_cookie(value, flag)).flop();
+    let timeout = mapping.get("timeout").map(
+        |value| value.as_u64().map(Duration::from_secs).ok_or_else(|| get_type(value))
+    ).flop();
+    let allowed_urls = mapping.get("allowed_urls").map(parse_allowed_urls).flop();
+
+    match (visits, cookie, timeout, allowed_urls) {
+        (Ok(visits), Ok(cookie), Ok(timeout), Ok(allowed_urls)) => Ok(AdminBot {
+            visits,
+            cookie,
+            timeout: timeout.unwrap_or(Duration::from_secs(DEFAULT_VISIT_TIMEOUT)),
+            allowed_urls: allowed_urls.unwrap_or_default(),
+        }),
+        (visits, cookie, timeout, allowed_urls) => Err(AdminBotError::Parts {
+            visits: visits.err(),
+            cookie: cookie.err(),
+            timeout_invalid: timeout.err(),
+            allowed_urls: allowed_urls.err(),
+        }),
+    }
+}
+
+pub fn verify_admin_bot(admin_bot: &AdminBot, deploy: Option<&DeployOptions>) -> Result<(), AdminBotError> {
+    if !deploy.is_some_and(|deploy| deploy.has_target(DeployTargetType::Admin)) {
+        return Err(AdminBotError::NoAdminTarget);
+    }
+    if !deploy.is_some_and(|deploy| deploy.has_target(admin_bot.visits)) {
+        return Err(AdminBotError::UndeclaredTarget(admin_bot.visits));
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::chall_slug;
+use super::structs::{DeployOptions, DeployTarget};
+
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComposeFile {
+    pub services: BTreeMap<String, ComposeService>,
+    pub networks: BTreeMap<String, ComposeNetwork>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComposeService {
+    pub build: ComposeBuild,
+    pub ports: Vec<String>,
+    pub deploy: ComposeDeploy,
+    pub networks: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComposeBuild {
+    pub context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComposeDeploy {
+    pub replicas: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ComposeNetwork {}
+
+
+fn build_context(build: &Path) -> String {
+    let build = build.to_string_lossy();
+    if build == "." {
+        ".".to_string()
+    } else {
+        format!("./{}", build.trim_start_matches("./"))
+    }
+}
+
+/// A single replica gets the same host port as it will have when deployed.
+/// Extra replicas can't share a host port, so docker picks one for each.
+fn port_mapping(target: &DeployTarget) -> String {
+    let port = target.expose.port();
+    let protocol = target.expose.protocol_str();
+    if target.replicas == 1 {
+        format!("{port}:{port}/{protocol}")
+    } else {
+        format!("{port}/{protocol}")
+    }
+}
+
+pub fn generate_compose(chall_name: &str, deploy: &DeployOptions) -> ComposeFile {
+    let network = chall_slug(chall_name);
+
+    let services = deploy
+        .clone()
+        .into_iter()
+        .map(|(target, target_type)| (
+            target_type.as_str().to_string(),
+            ComposeService {
+                build: ComposeBuild {
+                    context: build_context(&target.build),
+                    dockerfile: target.dockerfile.as_ref().map(|path| path.to_string_lossy().into_owned()),
+                },
+                ports: vec![port_mapping(&target)],
+                deploy: ComposeDeploy { replicas: target.replicas },
+                networks: vec![network.clone()],
+            },
+        ))
+        .collect();
+
+    ComposeFile {
+        services,
+        networks: BTreeMap::from([(network, ComposeNetwork::default())]),
+    }
+}
+
+impl ComposeFile {
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("compose files are always representable as yaml")
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::correctness::BaseImageCorrectness;
+use crate::paths::normalize;
+
+use super::error::{DockerfileError, DockerfileWarning};
+use super::structs::{DeployOptions, DeployTarget, DeployTargetType, NetworkProtocol};
+
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dockerfile {
+    pub base_images: Vec<String>,
+    pub exposed: Vec<ExposedPorts>,
+    pub unresolved_exposes: Vec<String>,
+    pub copies: Vec<DockerCopy>,
+}
+
+/// A port or range of ports from an `EXPOSE` line, like `9000-9001/udp`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExposedPorts {
+    pub start: u16,
+    pub end: u16,
+    pub is_tcp: bool,
+}
+impl ExposedPorts {
+    pub fn contains(&self, protocol: &NetworkProtocol) -> bool {
+        self.is_tcp == protocol.is_tcp() && (u32::from(self.start)..=u32::from(self.end)).contains(&protocol.port())
+    }
+}
+impl Display for ExposedPorts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let protocol = if self.is_tcp { "tcp" } else { "udp" };
+        if self.start == self.end {
+            write!(f, "{}/{protocol}", self.start)
+        } else {
+            write!(f, "{}-{}/{protocol}", self.start, self.end)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DockerCopy {
+    pub line: usize,
+    pub sources: Vec<String>,
+}
+
+/// Joins `\`-continued lines and drops comments, keeping the line number each
+/// instruction started on.
+fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines = vec![];
+    let mut current: Option<(usize, String)> = None;
+
+    for (idx, raw) in text.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.starts_with('#') || (trimmed.is_empty() && current.is_none()) {
+            continue;
+        }
+
+        let (start, mut buf) = current.take().unwrap_or((idx + 1, String::new()));
+        if let Some(continued) = trimmed.strip_suffix('\\') {
+            buf.push_str(continued);
+            buf.push(' ');
+            current = Some((start, buf));
+        } else {
+            buf.push_str(trimmed);
+            lines.push((start, buf));
+        }
+    }
+    if let Some(last) = current {
+        lines.push(last);
+    }
+    lines
+}
+
+fn parse_expose_token(token: &str) -> Option<ExposedPorts> {
+    let (ports, is_tcp) = match token.split_once('/') {
+        Some((ports, "tcp")) => (ports, true),
+        Some((ports, "udp")) => (ports, false),
+        Some(_) => return None,
+        None => (token, true),
+    };
+    let (start, end) = if let Some((start, end)) = ports.split_once('-') {
+        (start.parse::<u16>().ok()?, end.parse::<u16>().ok()?)
+    } else {
+        let port = ports.parse::<u16>().ok()?;
+        (port, port)
+    };
+    (start <= end).then_some(ExposedPorts { start, end, is_tcp })
+}
+
+fn copy_sources(args: &str) -> Option<Vec<String>> {
+    let mut tokens = args.split_whitespace().peekable();
+    let mut rest = args;
+    while let Some(flag) = tokens.peek().filter(|tok| tok.starts_with("--")) {
+        if flag.starts_with("--from") {
+            return None;
+        }
+        rest = rest.trim_start().strip_prefix(*flag).unwrap_or(rest);
+        tokens.next();
+    }
+
+    let mut parts: Vec<String> = if rest.trim_start().starts_with('[') {
+        serde_yaml::from_str(rest.trim()).ok()?
+    } else {
+        tokens.map(str::to_string).collect()
+    };
+    parts.pop()?;
+    Some(parts)
+}
+
+pub fn parse_dockerfile(text: &str) -> Dockerfile {
+    let mut dockerfile = Dockerfile::default();
+    let mut stage_names: Vec<String> = vec![];
+
+    for (line, instruction) in logical_lines(text) {
+        let (keyword, args) = instruction
+            .split_once(char::is_whitespace)
+            .unwrap_or((&instruction, ""));
+
+        match keyword.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                let mut tokens = args.split_whitespace().filter(|tok| !tok.starts_with("--"));
+                let Some(image) = tokens.next() else { continue };
+                if !stage_names.iter().any(|stage| stage.eq_ignore_ascii_case(image)) {
+                    dockerfile.base_images.push(image.to_string());
+                }
+                if let (Some(as_kw), Some(name)) = (tokens.next(), tokens.next()) {
+                    if as_kw.eq_ignore_ascii_case("as") {
+                        stage_names.push(name.to_string());
+                    }
+                }
+            },
+            "EXPOSE" => for token in args.split_whitespace() {
+                match parse_expose_token(token) {
+                    Some(ports) => dockerfile.exposed.push(ports),
+                    None => dockerfile.unresolved_exposes.push(token.to_string()),
+                }
+            },
+            "COPY" | "ADD" => if let Some(sources) = copy_sources(args) {
+                let sources = sources
+                    .into_iter()
+                    .filter(|src| !src.contains("://"))
+                    .collect();
+                dockerfile.copies.push(DockerCopy { line, sources });
+            },
+            _ => (),
+        }
+    }
+
+    dockerfile
+}
+
+
+fn wildcard_match(pattern: &str, candidate: &str) -> bool {
+    fn inner(pattern: &[char], candidate: &[char]) -> bool {
+        match (pattern.first(), candidate.first()) {
+            (None, None) => true,
+            (Some('*'), _) => inner(&pattern[1..], candidate)
+                || (!candidate.is_empty() && inner(pattern, &candidate[1..])),
+            (Some('?'), Some(_)) => inner(&pattern[1..], &candidate[1..]),
+            (Some(p), Some(c)) if p == c => inner(&pattern[1..], &candidate[1..]),
+            _ => false,
+        }
+    }
+    let pattern: Vec<_> = pattern.chars().collect();
+    let candidate: Vec<_> = candidate.chars().collect();
+    inner(&pattern, &candidate)
+}
+
+/// Whether copying `source` (relative to `context`) would bring `flag` along
+/// with it, either directly, through a wildcard, or as part of a directory.
+fn copies_path(context: &Path, source: &str, flag: &Path) -> bool {
+    let source = normalize(&context.join(source));
+    if flag.starts_with(&source) {
+        return true;
+    }
+
+    let is_wildcard = source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(['*', '?']));
+    if !is_wildcard {
+        return false;
+    }
+
+    let (Some(parent), Some(pattern)) = (source.parent(), source.file_name().and_then(|n| n.to_str())) else {
+        return false;
+    };
+    let Ok(rest) = flag.strip_prefix(parent) else { return false };
+    rest.components()
+        .next()
+        .and_then(|first| first.as_os_str().to_str())
+        .is_some_and(|first| wildcard_match(pattern, first))
+}
+
+pub fn inspect_target(
+    target: &DeployTarget,
+    target_type: DeployTargetType,
+    base_path: &Path,
+    flag_path: Option<&Path>,
+    images: &BaseImageCorrectness,
+) -> (Vec<DockerfileError>, Vec<DockerfileWarning>) {
+    let context = normalize(&base_path.join(&target.build));
+    let dockerfile_path = normalize(&base_path.join(target.dockerfile_path()));
+
+    let text = match std::fs::read_to_string(&dockerfile_path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return (vec![], vec![DockerfileWarning::Missing(dockerfile_path)]);
+        },
+        Err(_) => return (vec![DockerfileError::Unreadable(dockerfile_path)], vec![]),
+    };
+    let dockerfile = parse_dockerfile(&text);
+
+    let mut errors = vec![];
+    let mut warnings = vec![];
+
+    if !dockerfile.exposed.iter().any(|ports| ports.contains(&target.expose)) {
+        if !dockerfile.unresolved_exposes.is_empty() {
+            warnings.push(DockerfileWarning::UnresolvedExpose(dockerfile.unresolved_exposes.clone()));
+        } else if dockerfile.exposed.is_empty() {
+            warnings.push(DockerfileWarning::NoExpose);
+        } else {
+            errors.push(DockerfileError::ExposeMismatch {
+                declared: target.expose,
+                found: dockerfile.exposed.clone(),
+            });
+        }
+    }
+
+    errors.extend(
+        dockerfile.base_images
+            .iter()
+            .filter(|image| !images.check(image))
+            .cloned()
+            .map(DockerfileError::BaseImageNotAllowed)
+    );
+
+    let copy_checked = (target_type.is_web() || target_type.is_admin()) && !target.allow_flag_copy;
+    if let (true, Some(flag_path)) = (copy_checked, flag_path) {
+        let flag = normalize(&base_path.join(flag_path));
+        for copy in &dockerfile.copies {
+            errors.extend(
+                copy.sources
+                    .iter()
+                    .filter(|source| copies_path(&context, source, &flag))
+                    .map(|source| DockerfileError::CopiesFlag { line: copy.line, source: source.clone() })
+            );
+        }
+    }
+
+    (errors, warnings)
+}
+
+pub type TargetDockerfileErrors = Vec<(DeployTargetType, Vec<DockerfileError>)>;
+pub type TargetDockerfileWarnings = Vec<(DeployTargetType, DockerfileWarning)>;
+
+pub fn inspect_deploy(
+    deploy: &DeployOptions,
+    base_path: &Path,
+    flag_path: Option<&Path>,
+    images: &BaseImageCorrectness,
+) -> (TargetDockerfileErrors, TargetDockerfileWarnings) {
+    let mut errors = vec![];
+    let mut warnings = vec![];
+
+    for (target, target_type) in deploy.clone() {
+        let (target_errors, target_warnings) = inspect_target(&target, target_type, base_path, flag_path, images);
+        if !target_errors.is_empty() {
+            errors.push((target_type, target_errors));
+        }
+        warnings.extend(target_warnings.into_iter().map(|warning| (target_type, warning)));
+    }
+
+    (errors, warnings)
+}
@@ -2,6 +2,9 @@ use std::fmt::Display;
 
 use crate::structs::ValueType;
 
+use super::dockerfile::ExposedPorts;
+use super::structs::{DeployTargetType, NetworkProtocol};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExposeError {
     Missing,
@@ -37,6 +40,7 @@ pub enum BuildError {
     BadType(ValueType),
     NotPath(String),
     NotRelative(std::path::PathBuf),
+    EscapesRoot(std::path::PathBuf),
 }
 
 impl Display for BuildError {
@@ -45,6 +49,24 @@ impl Display for BuildError {
             Self::BadType(t) => writeln!(f, "Build should be a relative path, not {t}."),
             Self::NotPath(s) => writeln!(f, "Build should be a VALID relative path. \"{s}\" is not a valid path."),
             Self::NotRelative(p) => writeln!(f, "Build should be a RELATIVE path. \"{}\" is not a relative path.", p.display()),
+            Self::EscapesRoot(p) => writeln!(f, "Build should be inside the challenge directory. \"{}\" is outside of it.", p.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DockerfilePathError {
+    BadType(ValueType),
+    NotRelative(std::path::PathBuf),
+    EscapesRoot(std::path::PathBuf),
+}
+
+impl Display for DockerfilePathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadType(t) => write!(f, "`dockerfile` should be a path relative to the build context, not {t}."),
+            Self::NotRelative(p) => write!(f, "`dockerfile` should be a RELATIVE path. \"{}\" is not a relative path.", p.display()),
+            Self::EscapesRoot(p) => write!(f, "`dockerfile` should be inside the challenge directory. \"{}\" is outside of it.", p.display()),
         }
     }
 }
@@ -56,6 +78,8 @@ pub enum DeploymentTargetOptionsError {
         expose: Option<ExposeError>,
         replicas_invalid: Option<ValueType>, 
         build: Option<BuildError>,   
+        dockerfile: Option<DockerfilePathError>,
+        allow_flag_copy_invalid: Option<ValueType>,
     }
 }
 
@@ -67,6 +91,8 @@ impl Display for DeploymentTargetOptionsError {
                 expose,
                 replicas_invalid,
                 build,
+                dockerfile,
+                allow_flag_copy_invalid,
             } => {
                 writeln!(f, "There were issues with certain parts of this target:")?;
                 if let Some(expose_error) = expose {
@@ -78,6 +104,12 @@ impl Display for DeploymentTargetOptionsError {
                 if let Some(build) = build {
                     writeln!(f, "            {build}.")?;
                 }
+                if let Some(dockerfile) = dockerfile {
+                    writeln!(f, "            {dockerfile}")?;
+                }
+                if let Some(invalid_type) = allow_flag_copy_invalid {
+                    writeln!(f, "            `allow_flag_copy` should be a boolean, not {invalid_type}.")?;
+                }
                 Ok(())
             }
         }
@@ -85,29 +117,237 @@ impl Display for DeploymentTargetOptionsError {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+pub enum StaticDirError {
+    Missing,
+    BadType(ValueType),
+    NotRelative(std::path::PathBuf),
+    NotDirectory(std::path::PathBuf),
+    EscapesRoot(std::path::PathBuf),
+}
+
+impl Display for StaticDirError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "You must specify the `dir` to serve."),
+            Self::BadType(t) => write!(f, "`dir` should be a relative path, not {t}."),
+            Self::NotRelative(p) => write!(f, "`dir` should be a RELATIVE path. \"{}\" is not a relative path.", p.display()),
+            Self::NotDirectory(p) => write!(f, "`dir` should be a directory. \"{}\" is not a directory.", p.display()),
+            Self::EscapesRoot(p) => write!(f, "`dir` should be inside the challenge directory. \"{}\" is outside of it.", p.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaticTargetError {
+    BadBaseType(ValueType),
+    Parts {
+        dir: Option<StaticDirError>,
+        index_invalid: Option<ValueType>,
+        cache_invalid: Option<ValueType>,
+    },
+    MissingIndex(std::path::PathBuf),
+}
+
+impl Display for StaticTargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadBaseType(t) => writeln!(f, "A static target should be a map with values for `dir` and (optionally) `index` and `cache`, not {t}."),
+            Self::Parts {
+                dir,
+                index_invalid,
+                cache_invalid,
+            } => {
+                writeln!(f, "There were issues with certain parts of this target:")?;
+                if let Some(dir_error) = dir {
+                    writeln!(f, "            {dir_error}")?;
+                }
+                if let Some(invalid_type) = index_invalid {
+                    writeln!(f, "            `index` should be a file name, not {invalid_type}.")?;
+                }
+                if let Some(invalid_type) = cache_invalid {
+                    writeln!(f, "            `cache` should be a `Cache-Control` header value, not {invalid_type}.")?;
+                }
+                Ok(())
+            },
+            Self::MissingIndex(p) => writeln!(f, "The index file `{}` doesn't exist.", p.display()),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
 pub enum DeployOptionsError {
     Parts {
         web: Box<Option<DeploymentTargetOptionsError>>,
         admin: Box<Option<DeploymentTargetOptionsError>>,
         nc: Box<Option<DeploymentTargetOptionsError>>,
+        static_site: Box<Option<StaticTargetError>>,
     },
     BadBaseType(ValueType),
+    Dockerfiles(Vec<(DeployTargetType, Vec<DockerfileError>)>),
+    #[default]
+    MissingKey,
 }
 impl Display for DeployOptionsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::BadBaseType(t) => writeln!(f, "Deploy should be a map of `web`, `admin`, and `nc`, not {t}."),
+            Self::BadBaseType(t) => writeln!(f, "Deploy should be a map of `web`, `admin`, `nc`, and `static`, not {t}."),
+            Self::MissingKey => writeln!(f, "You have to define `deploy`."),
             Self::Parts {
                 web,
                 admin,
-                nc
+                nc,
+                static_site,
             } => {
                 writeln!(f, "There were issues with certain deployment targets:")?;
                 if let Some(web  ) = &**web   { writeln!(f, "        web:   {web}")?;   }
                 if let Some(admin) = &**admin { writeln!(f, "        admin: {admin}")?; }
                 if let Some(nc   ) = &**nc    { writeln!(f, "        nc:    {nc}")?;    }
+                if let Some(site ) = &**static_site { writeln!(f, "        static: {site}")?; }
                 Ok(())
             }
+            Self::Dockerfiles(targets) => {
+                writeln!(f, "There were issues with the Dockerfiles of certain deployment targets:")?;
+                for (target, errors) in targets {
+                    writeln!(f, "        {target}:")?;
+                    for error in errors {
+                        writeln!(f, "            {error}")?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DockerfileError {
+    Unreadable(std::path::PathBuf),
+    ExposeMismatch {
+        declared: NetworkProtocol,
+        found: Vec<ExposedPorts>,
+    },
+    BaseImageNotAllowed(String),
+    CopiesFlag {
+        line: usize,
+        source: String,
+    },
+}
+impl Display for DockerfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreadable(p) => write!(f, "There was an issue reading the Dockerfile at `{}`. Maybe check permissions?", p.display()),
+            Self::ExposeMismatch { declared, found } => {
+                write!(f, "The Dockerfile does not `EXPOSE` {declared}. It exposes ")?;
+                let found: Vec<_> = found.iter().map(ToString::to_string).collect();
+                write!(f, "{}.", found.join(", "))
+            },
+            Self::BaseImageNotAllowed(image) => write!(f, "The base image `{image}` is not on the list of allowed images."),
+            Self::CopiesFlag { line, source } => write!(f, "Line {line} copies `{source}`, which includes the flag file. (set `allow_flag_copy: true` if this is intended)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DockerfileWarning {
+    Missing(std::path::PathBuf),
+    NoExpose,
+    UnresolvedExpose(Vec<String>),
+}
+impl Display for DockerfileWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(p) => write!(f, "There is no Dockerfile at `{}`, so it could not be inspected.", p.display()),
+            Self::NoExpose => write!(f, "The Dockerfile has no `EXPOSE` instruction."),
+            Self::UnresolvedExpose(tokens) => write!(f, "The Dockerfile exposes {tokens:?}, which could not be checked against `expose`."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VisitsError {
+    Missing,
+    NotStr(ValueType),
+    NotWeb(String),
+}
+impl Display for VisitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "You must specify which target the admin bot `visits`."),
+            Self::NotStr(t) => write!(f, "`visits` should be the name of a web target, not {t}."),
+            Self::NotWeb(s) => write!(f, "`visits` should be the name of a web target. \"{s}\" is not one. (the only web target is `web`)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieError {
+    BadBaseType(ValueType),
+    BadField(&'static str, ValueType),
+}
+impl Display for CookieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadBaseType(t) => write!(f, "`cookie` should be a map with `name`, `value`, and (optionally) `domain`, not {t}."),
+            Self::BadField(key, t) => write!(f, "`cookie.{key}` should be a string, not {t}."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AllowedUrlsError {
+    BadType(ValueType),
+    BadEntryType(ValueType),
+    BadRegex(String),
+}
+impl Display for AllowedUrlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadType(t) => write!(f, "`allowed_urls` should be a list of regexes, not {t}."),
+            Self::BadEntryType(t) => write!(f, "Each entry of `allowed_urls` should be a regex string, not {t}."),
+            Self::BadRegex(s) => write!(f, "`{s}` in `allowed_urls` is not a valid regex."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminBotError {
+    BadBaseType(ValueType),
+    Parts {
+        visits: Option<VisitsError>,
+        cookie: Option<CookieError>,
+        timeout_invalid: Option<ValueType>,
+        allowed_urls: Option<AllowedUrlsError>,
+    },
+    NoAdminTarget,
+    UndeclaredTarget(DeployTargetType),
+}
+impl Display for AdminBotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadBaseType(t) => writeln!(f, "`admin_bot` should be a map, not {t}."),
+            Self::Parts {
+                visits,
+                cookie,
+                timeout_invalid,
+                allowed_urls,
+            } => {
+                writeln!(f, "There were issues with certain parts of `admin_bot`:")?;
+                if let Some(visits) = visits {
+                    writeln!(f, "        {visits}")?;
+                }
+                if let Some(cookie) = cookie {
+                    writeln!(f, "        {cookie}")?;
+                }
+                if let Some(invalid_type) = timeout_invalid {
+                    writeln!(f, "        `timeout` should be a whole number of seconds, not {invalid_type}.")?;
+                }
+                if let Some(allowed_urls) = allowed_urls {
+                    writeln!(f, "        {allowed_urls}")?;
+                }
+                Ok(())
+            },
+            Self::NoAdminTarget => writeln!(f, "`admin_bot` is defined, but there is no `admin` target under `deploy`."),
+            Self::UndeclaredTarget(target) => writeln!(f, "The admin bot visits `{target}`, but there is no `{target}` target under `deploy`."),
         }
     }
 }
@@ -0,0 +1,221 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::chall_slug;
+use super::structs::{DeployOptions, DeployTarget, DeployTargetType};
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct K8sConfig {
+    /// `{chall}` and `{target}` are replaced with the challenge slug and the
+    /// deploy target name.
+    pub image_template: Cow<'static, str>,
+    pub namespace: Option<Cow<'static, str>>,
+    pub network_policy: bool,
+}
+impl Default for K8sConfig {
+    fn default() -> Self {
+        Self {
+            image_template: Cow::Borrowed("{chall}-{target}:latest"),
+            namespace: None,
+            network_policy: false,
+        }
+    }
+}
+impl K8sConfig {
+    pub fn image_name(&self, chall_name: &str, target: DeployTargetType) -> String {
+        self.image_template
+            .replace("{chall}", &chall_slug(chall_name))
+            .replace("{target}", target.as_str())
+    }
+}
+
+
+type Labels = BTreeMap<&'static str, String>;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct K8sManifest {
+    pub api_version: &'static str,
+    pub kind: &'static str,
+    pub metadata: Metadata,
+    pub spec: ManifestSpec,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metadata {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub labels: Labels,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ManifestSpec {
+    Deployment(DeploymentSpec),
+    Service(ServiceSpec),
+    NetworkPolicy(NetworkPolicySpec),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeploymentSpec {
+    pub replicas: u8,
+    pub selector: Selector,
+    pub template: PodTemplate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Selector {
+    pub match_labels: Labels,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PodTemplate {
+    pub metadata: PodMetadata,
+    pub spec: PodSpec,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PodMetadata {
+    pub labels: Labels,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PodSpec {
+    pub containers: Vec<Container>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Container {
+    pub name: &'static str,
+    pub image: String,
+    pub ports: Vec<ContainerPort>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerPort {
+    pub container_port: u32,
+    pub protocol: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceSpec {
+    pub selector: Labels,
+    pub ports: Vec<ServicePort>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicePort {
+    pub name: &'static str,
+    pub port: u32,
+    pub target_port: u32,
+    pub protocol: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkPolicySpec {
+    pub pod_selector: Selector,
+    pub policy_types: Vec<&'static str>,
+    pub ingress: Vec<IngressRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngressRule {
+    pub ports: Vec<PolicyPort>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyPort {
+    pub port: u32,
+    pub protocol: &'static str,
+}
+
+
+fn target_manifests(
+    chall_name: &str,
+    target: &DeployTarget,
+    target_type: DeployTargetType,
+    config: &K8sConfig,
+) -> Vec<K8sManifest> {
+    let slug = chall_slug(chall_name);
+    let labels = Labels::from([
+        ("app", slug.clone()),
+        ("target", target_type.as_str().to_string()),
+    ]);
+    let metadata = Metadata {
+        name: format!("{slug}-{target_type}"),
+        namespace: config.namespace.as_ref().map(|namespace| namespace.to_string()),
+        labels: labels.clone(),
+    };
+
+    let port = target.expose.port();
+    let protocol = if target.expose.is_tcp() { "TCP" } else { "UDP" };
+
+    let deployment = K8sManifest {
+        api_version: "apps/v1",
+        kind: "Deployment",
+        metadata: metadata.clone(),
+        spec: ManifestSpec::Deployment(DeploymentSpec {
+            replicas: target.replicas,
+            selector: Selector { match_labels: labels.clone() },
+            template: PodTemplate {
+                metadata: PodMetadata { labels: labels.clone() },
+                spec: PodSpec {
+                    containers: vec![Container {
+                        name: target_type.as_str(),
+                        image: config.image_name(chall_name, target_type),
+                        ports: vec![ContainerPort { container_port: port, protocol }],
+                    }],
+                },
+            },
+        }),
+    };
+
+    let service = K8sManifest {
+        api_version: "v1",
+        kind: "Service",
+        metadata: metadata.clone(),
+        spec: ManifestSpec::Service(ServiceSpec {
+            selector: labels.clone(),
+            ports: vec![ServicePort { name: target_type.as_str(), port, target_port: port, protocol }],
+        }),
+    };
+
+    let mut manifests = vec![deployment, service];
+    if config.network_policy {
+        manifests.push(K8sManifest {
+            api_version: "networking.k8s.io/v1",
+            kind: "NetworkPolicy",
+            metadata,
+            spec: ManifestSpec::NetworkPolicy(NetworkPolicySpec {
+                pod_selector: Selector { match_labels: labels },
+                policy_types: vec!["Ingress"],
+                ingress: vec![IngressRule { ports: vec![PolicyPort { port, protocol }] }],
+            }),
+        });
+    }
+    manifests
+}
+
+pub fn generate_manifests(chall_name: &str, deploy: &DeployOptions, config: &K8sConfig) -> Vec<K8sManifest> {
+    deploy
+        .clone()
+        .into_iter()
+        .flat_map(|(target, target_type)| target_manifests(chall_name, &target, target_type, config))
+        .collect()
+}
+
+pub fn manifests_to_yaml(manifests: &[K8sManifest]) -> String {
+    manifests
+        .iter()
+        .map(|manifest| serde_yaml::to_string(manifest).expect("manifests are always representable as yaml"))
+        .collect::<Vec<_>>()
+        .join("---\n")
+}
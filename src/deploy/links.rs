@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use super::chall_slug;
+use super::structs::{DeployLink, DeployOptions, DeployTarget, DeployTargetType};
+
+
+/// How player-facing connection info is built for a competition.
+///
+/// The templates can use `{chall}` (the challenge slug), `{host}`, and
+/// `{port}` (the exposed port of the target, not available for static sites).
+/// The file template gets `{file}`, the name a file is downloaded as, instead
+/// of `{port}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostConfig {
+    pub host: Cow<'static, str>,
+    pub web_template: Cow<'static, str>,
+    pub admin_template: Cow<'static, str>,
+    pub nc_template: Cow<'static, str>,
+    pub static_template: Cow<'static, str>,
+    pub file_template: Cow<'static, str>,
+}
+impl HostConfig {
+    pub fn new(host: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            host: host.into(),
+            web_template: Cow::Borrowed("https://{chall}.{host}/"),
+            admin_template: Cow::Borrowed("https://{chall}-admin.{host}/"),
+            nc_template: Cow::Borrowed("nc {host} {port}"),
+            static_template: Cow::Borrowed("https://{host}/static/{chall}/"),
+            file_template: Cow::Borrowed("https://{host}/files/{chall}/{file}"),
+        }
+    }
+
+    pub fn with_web(self, web_template: impl Into<Cow<'static, str>>) -> Self { Self { web_template: web_template.into(), ..self } }
+    pub fn with_admin(self, admin_template: impl Into<Cow<'static, str>>) -> Self { Self { admin_template: admin_template.into(), ..self } }
+    pub fn with_nc(self, nc_template: impl Into<Cow<'static, str>>) -> Self { Self { nc_template: nc_template.into(), ..self } }
+    pub fn with_static(self, static_template: impl Into<Cow<'static, str>>) -> Self { Self { static_template: static_template.into(), ..self } }
+    pub fn with_file(self, file_template: impl Into<Cow<'static, str>>) -> Self { Self { file_template: file_template.into(), ..self } }
+
+    pub fn link(&self, chall_name: &str, target: &DeployTarget, target_type: DeployTargetType) -> DeployLink {
+        use DeployTargetType::*;
+        let template = match target_type {
+            Web => &self.web_template,
+            Admin => &self.admin_template,
+            Nc => &self.nc_template,
+            Static => &self.static_template,
+        };
+        let link = template
+            .replace("{chall}", &chall_slug(chall_name))
+            .replace("{host}", &self.host)
+            .replace("{port}", &target.expose.port().to_string());
+
+        DeployLink { deploy_target: target_type, link }
+    }
+
+    pub fn static_link(&self, chall_name: &str) -> DeployLink {
+        let link = self.static_template
+            .replace("{chall}", &chall_slug(chall_name))
+            .replace("{host}", &self.host);
+
+        DeployLink { deploy_target: DeployTargetType::Static, link }
+    }
+
+    pub fn file_link(&self, chall_name: &str, file_name: &str) -> String {
+        self.file_template
+            .replace("{chall}", &chall_slug(chall_name))
+            .replace("{host}", &self.host)
+            .replace("{file}", file_name)
+    }
+}
+
+pub fn deploy_links(chall_name: &str, deploy: &DeployOptions, config: &HostConfig) -> Vec<DeployLink> {
+    deploy
+        .clone()
+        .into_iter()
+        .map(|(target, target_type)| config.link(chall_name, &target, target_type))
+        .chain(deploy.static_site.as_ref().map(|_| config.static_link(chall_name)))
+        .collect()
+}
@@ -1,22 +1,46 @@
 pub mod error;
 pub mod structs;
+pub mod dockerfile;
+pub mod compose;
+pub mod k8s;
+pub mod links;
+pub mod admin_bot;
 
 
 
 use serde_yaml::Value as YamlValue;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
 
+use crate::files::digest::{sha256, to_hex};
+use crate::paths::is_contained;
 use crate::structs::get_type;
 use crate::Flop;
 
 use self::{
-    error::{DeployOptionsError, DeploymentTargetOptionsError, ExposeError, BuildError},
-    structs::{DeployOptions, DeployTarget, NetworkProtocol},
+    error::{DeployOptionsError, DeploymentTargetOptionsError, ExposeError, BuildError, DockerfilePathError, StaticTargetError, StaticDirError},
+    structs::{DeployOptions, DeployTarget, NetworkProtocol, StaticTarget},
 };
 
 
 const DEFAULT_REPLICAS: u8 = 1;
+const DEFAULT_STATIC_INDEX: &str = "index.html";
+
+/// Turns a challenge name into something usable as a container, network, or
+/// image name: lowercase ascii alphanumerics separated by single dashes.
+/// Names without any ascii alphanumerics get `chall-` and part of their hash.
+pub fn chall_slug(name: &str) -> String {
+    let slug = name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        format!("chall-{}", to_hex(&sha256(name.as_bytes())[..4]))
+    } else {
+        slug
+    }
+}
 
 pub fn parse_expose(expose: &str) -> Result<NetworkProtocol, ExposeError> {
 
@@ -47,7 +71,7 @@ pub fn parse_expose(expose: &str) -> Result<NetworkProtocol, ExposeError> {
     }
 }
 
-pub fn parse_deploy_target(value: &YamlValue) -> Result<DeployTarget, DeploymentTargetOptionsError> {
+pub fn parse_deploy_target(value: &YamlValue, base_path: &Path, shared_roots: &[PathBuf]) -> Result<DeployTarget, DeploymentTargetOptionsError> {
     let mapping = value.as_mapping().ok_or_else(|| DeploymentTargetOptionsError::BadBaseType(get_type(value)))?;
 
 
@@ -66,17 +90,37 @@ pub fn parse_deploy_target(value: &YamlValue) -> Result<DeployTarget, Deployment
             break 'path_block Err(BuildError::BadType(get_type(value)))
         };
 
-        let Ok(path) = PathBuf::from_str(string) else {
-            break 'path_block Err(BuildError::NotPath(string.to_string()))
-        };
+        let path = PathBuf::from(string);
     
         if !path.is_relative() {
             break 'path_block Err(BuildError::NotRelative(path));
         }
+        if !is_contained(base_path, &path, shared_roots) {
+            break 'path_block Err(BuildError::EscapesRoot(path));
+        }
 
         Ok(path)
     };
 
+    let dockerfile = mapping.get("dockerfile").map(|value| {
+        let Some(string) = value.as_str() else {
+            return Err(DockerfilePathError::BadType(get_type(value)))
+        };
+
+        let path = PathBuf::from(string);
+
+        if !path.is_relative() {
+            return Err(DockerfilePathError::NotRelative(path));
+        }
+        if let Ok(build) = &build {
+            if !is_contained(base_path, &build.join(&path), shared_roots) {
+                return Err(DockerfilePathError::EscapesRoot(path));
+            }
+        }
+
+        Ok(path)
+    }).flop();
+
     let replicas = mapping.get("replicas").map(|replicas_val| {
         replicas_val
             .as_u64()
@@ -85,45 +129,118 @@ pub fn parse_deploy_target(value: &YamlValue) -> Result<DeployTarget, Deployment
             .ok_or_else(|| get_type(replicas_val))
     }).flop();
 
-    match (expose, replicas, build) {
-        (Ok(expose), Ok(replicas), Ok(build)) => Ok(DeployTarget {
+    let allow_flag_copy = mapping.get("allow_flag_copy").map(|allow_val| {
+        allow_val
+            .as_bool()
+            .ok_or_else(|| get_type(allow_val))
+    }).flop();
+
+    match (expose, replicas, build, dockerfile, allow_flag_copy) {
+        (Ok(expose), Ok(replicas), Ok(build), Ok(dockerfile), Ok(allow_flag_copy)) => Ok(DeployTarget {
             expose,
             replicas: replicas.unwrap_or(DEFAULT_REPLICAS),
             build,
+            dockerfile,
+            allow_flag_copy: allow_flag_copy.unwrap_or(false),
         }),
-        (expose, replicas, build) => Err(DeploymentTargetOptionsError::Parts {
+        (expose, replicas, build, dockerfile, allow_flag_copy) => Err(DeploymentTargetOptionsError::Parts {
             expose: expose.err(),
             replicas_invalid: replicas.err(),
             build: build.err(),
+            dockerfile: dockerfile.err(),
+            allow_flag_copy_invalid: allow_flag_copy.err(),
         })
     }
 
 }
 
-pub fn parse_deploy(value: &YamlValue) -> Result<DeployOptions, DeployOptionsError> {
+pub fn parse_static_target(value: &YamlValue, base_path: &Path, shared_roots: &[PathBuf]) -> Result<StaticTarget, StaticTargetError> {
+    let mapping = value.as_mapping().ok_or_else(|| StaticTargetError::BadBaseType(get_type(value)))?;
+
+    let dir = 'dir_block: {
+        let Some(value) = mapping.get("dir") else {
+            break 'dir_block Err(StaticDirError::Missing)
+        };
+
+        let Some(string) = value.as_str() else {
+            break 'dir_block Err(StaticDirError::BadType(get_type(value)))
+        };
+
+        let path = PathBuf::from(string);
+        if !path.is_relative() {
+            break 'dir_block Err(StaticDirError::NotRelative(path));
+        }
+        if !is_contained(base_path, &path, shared_roots) {
+            break 'dir_block Err(StaticDirError::EscapesRoot(path));
+        }
+        if !base_path.join(&path).is_dir() {
+            break 'dir_block Err(StaticDirError::NotDirectory(path));
+        }
+
+        Ok(path)
+    };
+
+    let index = mapping.get("index").map(|index_val| {
+        index_val
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| get_type(index_val))
+    }).flop();
+
+    let cache = mapping.get("cache").map(|cache_val| {
+        cache_val
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| get_type(cache_val))
+    }).flop();
+
+    match (dir, index, cache) {
+        (Ok(dir), Ok(index), Ok(cache)) => {
+            let index = index.unwrap_or_else(|| DEFAULT_STATIC_INDEX.to_string());
+            if base_path.join(&dir).join(&index).is_file() {
+                Ok(StaticTarget { dir, index, cache })
+            } else {
+                Err(StaticTargetError::MissingIndex(dir.join(index)))
+            }
+        },
+        (dir, index, cache) => Err(StaticTargetError::Parts {
+            dir: dir.err(),
+            index_invalid: index.err(),
+            cache_invalid: cache.err(),
+        }),
+    }
+}
+
+pub fn parse_deploy(value: &YamlValue, base_path: &Path, shared_roots: &[PathBuf]) -> Result<DeployOptions, DeployOptionsError> {
     let mapping = value.as_mapping().ok_or_else(|| DeployOptionsError::BadBaseType(get_type(value)))?;
 
     let web = mapping
         .get("web")
-        .map(parse_deploy_target)
+        .map(|value| parse_deploy_target(value, base_path, shared_roots))
         .flop();
 
     let admin = mapping
         .get("admin")
-        .map(parse_deploy_target)
+        .map(|value| parse_deploy_target(value, base_path, shared_roots))
         .flop();
 
     let nc = mapping
         .get("nc")
-        .map(parse_deploy_target)
+        .map(|value| parse_deploy_target(value, base_path, shared_roots))
+        .flop();
+
+    let static_site = mapping
+        .get("static")
+        .map(|value| parse_static_target(value, base_path, shared_roots))
         .flop();
 
-    match (web, admin, nc) {
-        (Ok(web), Ok(admin), Ok(nc)) => Ok(DeployOptions { web, admin, nc }),
-        (web, admin, nc) => Err(DeployOptionsError::Parts {
+    match (web, admin, nc, static_site) {
+        (Ok(web), Ok(admin), Ok(nc), Ok(static_site)) => Ok(DeployOptions { web, admin, nc, static_site }),
+        (web, admin, nc, static_site) => Err(DeployOptionsError::Parts {
             web: Box::new(web.err()),
             admin: Box::new(admin.err()),
             nc: Box::new(nc.err()),
+            static_site: Box::new(static_site.err()),
         })
     }
 }
@@ -1,8 +1,10 @@
 use std::fmt::{Debug, Display};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Serialize, Serializer};
 
+use crate::files::structs::File;
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DeployLink {
     #[serde(rename = "type")]
@@ -15,6 +17,8 @@ pub struct DeployLink {
 pub enum DeployTargetType { Web, Admin, Nc, Static }
 
 impl DeployTargetType {
+    pub const ALL: [DeployTargetType; 4] = [Self::Web, Self::Admin, Self::Nc, Self::Static];
+
     pub fn is_web(&self) -> bool {
         matches!(self, Self::Web)
     }
@@ -28,6 +32,16 @@ impl DeployTargetType {
         matches!(self, Self::Static)
     }
 
+    pub fn try_from_str(s: &str) -> Option<Self> {
+        match s {
+            "web" => Some(Self::Web),
+            "admin" => Some(Self::Admin),
+            "nc" => Some(Self::Nc),
+            "static" => Some(Self::Static),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Web => "web",
@@ -66,8 +80,23 @@ pub struct DeployOptions {
     pub web: Option<DeployTarget>,
     pub admin: Option<DeployTarget>,
     pub nc: Option<DeployTarget>,
+    pub static_site: Option<StaticTarget>,
 }
 
+impl DeployOptions {
+    pub fn has_target(&self, target_type: DeployTargetType) -> bool {
+        use DeployTargetType::*;
+        match target_type {
+            Web => self.web.is_some(),
+            Admin => self.admin.is_some(),
+            Nc => self.nc.is_some(),
+            Static => self.static_site.is_some(),
+        }
+    }
+}
+
+/// Iterates over the container targets. The static site isn't a container,
+/// so it is left out.
 impl IntoIterator for DeployOptions {
     type IntoIter = std::iter::Flatten<std::array::IntoIter<Option<(DeployTarget, DeployTargetType)>, 3>>;
     type Item = (DeployTarget, DeployTargetType);
@@ -86,7 +115,31 @@ impl IntoIterator for DeployOptions {
 pub struct DeployTarget {
     pub expose: NetworkProtocol,
     pub build: PathBuf,
+    /// Relative to `build`. `None` means `build/Dockerfile`.
+    pub dockerfile: Option<PathBuf>,
     pub replicas: u8,
+    pub allow_flag_copy: bool,
+}
+
+impl DeployTarget {
+    /// Where the Dockerfile for this target is, relative to the challenge directory.
+    pub fn dockerfile_path(&self) -> PathBuf {
+        self.build.join(self.dockerfile.as_deref().unwrap_or(Path::new("Dockerfile")))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticTarget {
+    pub dir: PathBuf,
+    pub index: String,
+    pub cache: Option<String>,
+}
+
+/// A static site along with the files routed to it with `container: static`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticSite<'a> {
+    pub target: &'a StaticTarget,
+    pub files: Vec<&'a File>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -104,6 +157,14 @@ impl NetworkProtocol {
     pub fn is_udp(&self) -> bool {
         matches!(self, Self::Udp(_))
     }
+    pub fn protocol_str(&self) -> &'static str {
+        if self.is_tcp() { "tcp" } else { "udp" }
+    }
+}
+impl Display for NetworkProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.port(), self.protocol_str())
+    }
 }
 
 impl Debug for DeployTarget {
@@ -145,6 +206,9 @@ impl Debug for DeployOptions {
         if let Some(nc) = &self.nc {
             options_formatter.field("nc", nc);
         }
+        if let Some(static_site) = &self.static_site {
+            options_formatter.field("static", static_site);
+        }
         options_formatter.finish()
     }
 }
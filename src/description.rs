@@ -0,0 +1,100 @@
+use std::{fmt::{Display, Debug}, path::{PathBuf, Path}, io::ErrorKind};
+
+use serde_yaml::Value as YamlValue;
+
+use crate::paths::is_contained;
+use crate::structs::{get_type, ValueType};
+
+
+pub fn get_file_description(path: PathBuf, base_path: &Path, shared_roots: &[PathBuf]) -> Result<Description, DescriptionError> {
+    if !is_contained(base_path, &path, shared_roots) {
+        return Err(DescriptionError::EscapesRoot(path));
+    }
+    match std::fs::read_to_string(base_path.join(&path)) {
+        Ok(s) => Ok(Description::File(path, s)),
+        Err(e) => if e.kind() == ErrorKind::NotFound {
+            Err(DescriptionError::FileMissing(path))
+        } else {
+            Err(DescriptionError::OsError(path))
+        },
+    }
+}
+
+/// A description is either written out, or kept in a separate (Markdown) file
+/// with `description: { file: <path> }`.
+pub fn get_description(value: &YamlValue, base_path: &Path, shared_roots: &[PathBuf]) -> Result<Description, DescriptionError> {
+    if let Some(desc_str) = value.as_str() {
+        Ok(Description::String(desc_str.to_string()))
+    } else if let Some(mapping) = value.as_mapping() {
+        if let Some(Some(file)) = mapping.get("file").map(YamlValue::as_str) {
+            let path = PathBuf::from(file);
+            if path.is_relative() {
+                get_file_description(path, base_path, shared_roots)
+            } else {
+                Err(DescriptionError::BadPath(file.to_string()))
+            }
+        } else {
+            Err(DescriptionError::MappingNeedsFile)
+        }
+    } else {
+        Err(DescriptionError::BadType(get_type(value)))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Description {
+    String(String),
+    File(PathBuf, String),
+}
+impl Description {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::String(s) | Self::File(_, s) => s,
+        }
+    }
+    /// Where the description is kept, if it's in its own file.
+    pub fn path(&self) -> Option<&Path> {
+        if let Self::File(p, _) = self {
+            Some(p.as_path())
+        } else { None }
+    }
+}
+
+impl Debug for Description {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Description::{ String, File };
+        match self {
+            String(s) => write!(f, "Description< {s:?} >"),
+            File(p, s) => write!(f, "Description< {s:?} (@ {}) >", p.display()),
+        }
+    }
+}
+
+
+#[derive(Default, Debug, Clone)]
+pub enum DescriptionError {
+    BadType(ValueType),
+    BadPath(String),
+    MappingNeedsFile,
+    FileMissing(PathBuf),
+    OsError(PathBuf),
+    EscapesRoot(PathBuf),
+
+    #[default]
+    MissingKey,
+}
+
+impl Display for DescriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DescriptionError::*;
+        match self {
+            BadType(t) => write!(f, "The description should be a string, not {t}."),
+            BadPath(p) => write!(f, "The string {p} is not a valid path. (hint: If you want to write the description out, use `description: <text>`)"),
+            MappingNeedsFile => write!(f, "If you are going to keep the description in a file, you need to have `file: <path>` as an entry under `description`. (<path> must be a string)"),
+            MissingKey => write!(f, "You have to define `description`."),
+            FileMissing(p) => write!(f, "There is no file at {}.", p.display()),
+            OsError(p) => write!(f, "There was an issue opening the file at {}. Maybe check permissions?", p.display()),
+            EscapesRoot(p) => write!(f, "The description file {} is outside of the challenge directory.", p.display()),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::{Compression, GzBuilder};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use super::get_file::walk_dir;
+use super::structs::ArchiveFormat;
+
+
+/// Every entry gets the same permissions and timestamp so that building the
+/// same directory twice gives the exact same bytes.
+const ENTRY_MODE: u32 = 0o644;
+
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sorted_entries(base_path: &Path, dir: &Path, include_hidden: bool) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut files = vec![];
+    walk_dir(base_path, dir, include_hidden, &mut files)?;
+
+    let mut entries: Vec<_> = files
+        .into_iter()
+        .filter_map(|file| {
+            let name = entry_name(file.strip_prefix(dir).ok()?);
+            Some((name, base_path.join(file)))
+        })
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+fn build_zip(entries: &[(String, PathBuf)]) -> std::io::Result<Vec<u8>> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(ENTRY_MODE);
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, path) in entries {
+        writer.start_file(name, options)?;
+        writer.write_all(&std::fs::read(path)?)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+fn build_tar_gz(entries: &[(String, PathBuf)]) -> std::io::Result<Vec<u8>> {
+    let encoder = GzBuilder::new().mtime(0).write(Vec::new(), Compression::default());
+
+    let mut builder = tar::Builder::new(encoder);
+    for (name, path) in entries {
+        let data = std::fs::read(path)?;
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(ENTRY_MODE);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_entry_type(tar::EntryType::Regular);
+
+        builder.append_data(&mut header, name, data.as_slice())?;
+    }
+    builder.into_inner()?.finish()
+}
+
+pub fn build_archive(base_path: &Path, dir: &Path, format: ArchiveFormat, include_hidden: bool) -> std::io::Result<Vec<u8>> {
+    let entries = sorted_entries(base_path, dir, include_hidden)?;
+    match format {
+        ArchiveFormat::Zip => build_zip(&entries),
+        ArchiveFormat::TarGz => build_tar_gz(&entries),
+    }
+}
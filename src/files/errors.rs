@@ -3,6 +3,8 @@ use std::fmt::{ Display, Debug };
 
 use crate::structs::ValueType;
 
+use super::structs::{ContainerType, FileSource};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FileParseErr {
     ItemNotMapping(ValueType),
@@ -12,7 +14,10 @@ pub enum FileParseErr {
         visible: Option<ValueType>,
         alias: Option<ValueType>,
         container: Option<ContainerTypeErr>,
-        data: Option<DataReadErr>,
+        archive: Option<ArchiveErr>,
+        include_hidden: Option<ValueType>,
+        data: Option<Box<DataReadErr>>,
+        sha256: Option<Box<ChecksumErr>>,
     },
 }
 impl Display for FileParseErr {
@@ -25,7 +30,10 @@ impl Display for FileParseErr {
                 visible,
                 alias,
                 container,
+                archive,
+                include_hidden,
                 data,
+                sha256,
             } => {
                 writeln!(f, "There were issues with certain parts of this file entry:")?;
                 if let Some(path_err) = path {
@@ -40,9 +48,18 @@ impl Display for FileParseErr {
                 if let Some(cont_err) = container {
                     writeln!(f, "            {cont_err}")?;
                 }
+                if let Some(archive_err) = archive {
+                    writeln!(f, "            {archive_err}")?;
+                }
+                if let Some(hidden_err) = include_hidden {
+                    writeln!(f, "            `include_hidden` must be a boolean or undefined, not {hidden_err}")?;
+                }
                 if let Some(data_err) = data {
                     writeln!(f, "            {data_err}")?;
                 }
+                if let Some(sha_err) = sha256 {
+                    writeln!(f, "            {sha_err}")?;
+                }
                 
                 Ok(())
             },
@@ -55,14 +72,28 @@ pub enum FilePathErr {
     NoExist,
     NotStr(ValueType),
     BadPath(String),
+    BadPattern(String),
+    NoMatches(String),
+    AliasForMany(String, usize),
+    ChecksumForMany(String, usize),
+    EscapesRoot(PathBuf),
+    BadUrl(String),
+    SrcAndUrl,
 }
 impl Display for FilePathErr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use FilePathErr::*;
         match self {
-            NoExist => write!(f, "You must define `src`"),
+            NoExist => write!(f, "You must define `src` (or `url` for files stored elsewhere)"),
             NotStr(t) => write!(f, "The source path must be a filepath, not {t}"),
             BadPath(s) => write!(f, "The source `{s}` is not a valid filepath"),
+            BadPattern(s) => write!(f, "The source `{s}` is not a valid glob pattern"),
+            NoMatches(s) => write!(f, "The source `{s}` doesn't match any files"),
+            AliasForMany(s, n) => write!(f, "The source `{s}` matches {n} files, so it can't be given a single display name"),
+            ChecksumForMany(s, n) => write!(f, "The source `{s}` matches {n} files, so it can't be pinned to a single `sha256`"),
+            EscapesRoot(p) => write!(f, "The source `{}` is outside of the challenge directory", p.display()),
+            BadUrl(s) => write!(f, "The url `{s}` is not a valid http(s) url"),
+            SrcAndUrl => write!(f, "A file can have a `src` or a `url`, but not both"),
         }
     }
 }
@@ -82,11 +113,69 @@ impl Display for ContainerTypeErr {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchiveErr {
+    NotStr(ValueType),
+    BadFormat(String),
+    NotDirectory(FileSource),
+}
+impl Display for ArchiveErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ArchiveErr::*;
+        match self {
+            NotStr(t) => write!(f, "The archive format must be `zip` or `tar.gz`, not {t}"),
+            BadFormat(s) => write!(f, "The archive format must be `zip` or `tar.gz`. \"{s}\" is not one of those."),
+            NotDirectory(file) => write!(f, "Only directories can be archived, and `{file}` is not a directory."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChecksumErr {
+    NotStr(ValueType),
+    BadFormat(String),
+    Unverifiable,
+    MissingForUrl,
+    Mismatch {
+        file: FileSource,
+        expected: String,
+        actual: String,
+    },
+}
+impl Display for ChecksumErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ChecksumErr::*;
+        match self {
+            NotStr(t) => write!(f, "The `sha256` of a file must be a hex string, not {t}"),
+            BadFormat(s) => write!(f, "The `sha256` of a file must be 64 hex characters. \"{s}\" is not."),
+            Unverifiable => write!(f, "Files copied into a container aren't read, so their `sha256` can't be checked."),
+            MissingForUrl => write!(f, "Files given by `url` must have their `sha256` pinned."),
+            Mismatch { file, expected, actual } => write!(
+                f,
+                "The file at `{file}` has a SHA-256 of {actual}, but {expected} was expected. Is it stale or modified?",
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DataReadErr {
     DoesntExist(PathBuf),
     Canonicalize(PathBuf),
     OsError(PathBuf),
+    TooLarge {
+        file: FileSource,
+        size: u64,
+        limit: u64,
+    },
+    DisallowedType {
+        file: FileSource,
+        file_type: String,
+    },
+    FetchFailed {
+        url: String,
+        reason: String,
+    },
 }
 impl Display for DataReadErr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -95,20 +184,51 @@ impl Display for DataReadErr {
             DoesntExist(p) => write!(f, "The file path `{}` doesn't exist.", p.display()),
             Canonicalize(p) => write!(f, "There was an issue finding the absolute path to `{}`. Maybe it doesn't exist.", p.display()),
             OsError(p) => write!(f, "There was an issue reading the file at `{}`. Maybe check permissions?", p.display()),
+            TooLarge { file, size, limit } => write!(f, "The file at `{file}` is {size} bytes, which is over the limit of {limit} bytes."),
+            DisallowedType { file, file_type } => write!(f, "The file at `{file}` is a `{file_type}` file, which is not allowed."),
+            FetchFailed { url, reason } => write!(f, "Couldn't download `{url}`: {reason}"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub enum FileErrors {
     BadBaseType(ValueType),
     EntryErrors(Vec<Option<FileParseErr>>),
+    TotalTooLarge {
+        size: u64,
+        limit: u64,
+    },
+    FlagExposed(PathBuf),
+    FlagServed(PathBuf),
+    UndeclaredContainers(Vec<(FileSource, ContainerType)>),
+    DownloadNames(Vec<DownloadNameErr>),
+    #[default]
+    MissingKey,
 }
 impl Display for FileErrors {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use FileErrors::*;
         match self {
             BadBaseType(t) => write!(f, "Files should be a list, not {t}."),
+            MissingKey => write!(f, "You have to define `files`."),
+            TotalTooLarge { size, limit } => write!(f, "The files add up to {size} bytes, which is over the limit of {limit} bytes per challenge."),
+            FlagExposed(p) => write!(f, "The flag file would be given to players through `{}`. Mark it with `visible: false` if that's intended.", p.display()),
+            FlagServed(p) => write!(f, "The flag file is inside of `{}`, so the static site would serve it to players.", p.display()),
+            UndeclaredContainers(files) => {
+                writeln!(f, "Some files are routed to a container that isn't declared under `deploy`:")?;
+                for (file, container) in files {
+                    writeln!(f, "        `{file}` goes to `{}`, but there is no `{}` target.", container.to_str(), container.to_str())?;
+                }
+                Ok(())
+            },
+            DownloadNames(errs) => {
+                writeln!(f, "Some files can't be downloaded under their names:")?;
+                for err in errs {
+                    writeln!(f, "        {err}")?;
+                }
+                Ok(())
+            },
             EntryErrors(errs) => {
                 writeln!(f, "Some entries under `files` are invalid:")?;
                 let iter = errs
@@ -124,3 +244,51 @@ impl Display for FileErrors {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameIssue {
+    Empty,
+    Separator(char),
+    ControlChar,
+    ReservedChar(char),
+    TrailingDotOrSpace,
+    ReservedName,
+    TooLong(usize),
+}
+impl Display for NameIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use NameIssue::*;
+        match self {
+            Empty => write!(f, "it's empty"),
+            Separator(c) => write!(f, "it contains the path separator `{c}`"),
+            ControlChar => write!(f, "it contains a control character"),
+            ReservedChar(c) => write!(f, "`{c}` isn't allowed in Windows filenames"),
+            TrailingDotOrSpace => write!(f, "Windows drops trailing dots and spaces"),
+            ReservedName => write!(f, "it's a reserved device name on Windows"),
+            TooLong(len) => write!(f, "it's {len} bytes long, over the limit of 255"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadNameErr {
+    Unportable {
+        name: String,
+        file: FileSource,
+        issue: NameIssue,
+    },
+    Collision {
+        name: String,
+        first: FileSource,
+        second: FileSource,
+    },
+}
+impl Display for DownloadNameErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DownloadNameErr::*;
+        match self {
+            Unportable { name, file, issue } => write!(f, "`{file}` is downloaded as {name:?}, but {issue}."),
+            Collision { name, first, second } => write!(f, "`{first}` and `{second}` are both downloaded as {name:?}."),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+
+
+/// Downloads attachments that are given by `url` instead of `src`. Nothing is
+/// fetched unless one is set on the [`FilePolicy`](crate::correctness::FilePolicy),
+/// in which case the data is checked against the pinned `sha256`.
+pub trait Fetcher: Debug + Send + Sync {
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, String>;
+}
+
+#[derive(Debug, Clone)]
+pub struct SharedFetcher(pub Arc<dyn Fetcher>);
+
+impl PartialEq for SharedFetcher {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Arc::as_ptr(&self.0) as *const (),
+            Arc::as_ptr(&other.0) as *const (),
+        )
+    }
+}
+
+/// Where a file stored elsewhere is downloaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Url(String);
+impl Url {
+    pub fn as_str(&self) -> &str { &self.0 }
+
+    /// The last segment of the url's path, without the query or fragment.
+    pub fn file_name(&self) -> Option<&str> {
+        let rest = self.0.split_once("://").map_or(self.0.as_str(), |(_, rest)| rest);
+        let rest = rest.split(['?', '#']).next()?;
+        let (_, path) = rest.split_once('/')?;
+        path.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+}
+impl Display for Url {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Only plain `http(s)://host/...` urls, since they get handed to whatever
+/// downloads them as-is.
+pub fn parse_url(url: &str) -> Option<Url> {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let valid = !host.is_empty()
+        && !url.chars().any(|c| c.is_whitespace() || c.is_control());
+    valid.then(|| Url(url.to_string()))
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+
+/// Magic bytes (at an offset) for file types worth telling apart from their
+/// extension, usually because they're big or executable.
+const MAGIC: &[(&str, usize, &[u8])] = &[
+    ("elf", 0, b"\x7fELF"),
+    ("exe", 0, b"MZ"),
+    ("zip", 0, b"PK\x03\x04"),
+    ("gz", 0, b"\x1f\x8b"),
+    ("xz", 0, b"\xfd7zXZ\x00"),
+    ("bz2", 0, b"BZh"),
+    ("7z", 0, b"7z\xbc\xaf\x27\x1c"),
+    ("rar", 0, b"Rar!\x1a\x07"),
+    ("pdf", 0, b"%PDF"),
+    ("png", 0, b"\x89PNG\r\n\x1a\n"),
+    ("jpg", 0, b"\xff\xd8\xff"),
+    ("gif", 0, b"GIF8"),
+    ("sqlite", 0, b"SQLite format 3\x00"),
+    ("qcow2", 0, b"QFI\xfb"),
+    ("vmdk", 0, b"KDMV"),
+    ("vdi", 64, b"\x7f\x10\xda\xbe"),
+    ("iso", 0x8001, b"CD001"),
+];
+
+/// How much of the start of a file `magic_type` looks at.
+pub const MAGIC_LEN: usize = 0x8001 + b"CD001".len();
+
+pub fn magic_type(data: &[u8]) -> Option<&'static str> {
+    MAGIC
+        .iter()
+        .find(|(_, offset, magic)| data.get(*offset..).is_some_and(|data| data.starts_with(magic)))
+        .map(|(name, _, _)| *name)
+}
+
+/// Every type a file could be considered: its (lowercased) extension and
+/// whatever its magic bytes say it is.
+pub fn file_types(path: &Path, data: &[u8]) -> Vec<String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let magic = magic_type(data).map(str::to_string);
+
+    let mut types: Vec<_> = extension.into_iter().chain(magic).collect();
+    types.dedup();
+    types
+}
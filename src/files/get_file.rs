@@ -2,15 +2,20 @@
 
 use std::convert::identity;
 use std::path::{ Path, PathBuf };
-use std::str::FromStr;
 
 use serde_yaml::Mapping as YamlMapping;
 
 use crate::Flop;
+use crate::correctness::FilePolicy;
+use crate::paths::is_contained;
 use crate::structs::get_type;
 
-use super::structs::{ File, ContainerType };
-use super::errors::FileParseErr;
+use super::structs::{ File, FileSource, ContainerType, ArchiveFormat };
+use super::archive::build_archive;
+use super::digest::{sha256, parse_sha256, to_hex};
+use super::errors::{DataReadErr, FileParseErr, FilePathErr};
+use super::file_type::{file_types, MAGIC_LEN};
+use super::fetch::parse_url;
 
 macro_rules! get_req {
     (
@@ -19,10 +24,6 @@ macro_rules! get_req {
         $(($final_map:expr) else ($final_err:expr);)?
     ) => {
         'macro_block: {
-            fn call<T, R>(value: T, function: impl FnOnce(T) -> R) -> R {
-                (function)(value)
-            }
-
             let Some(value) = $base.get(stringify!($key)) else {
                 break 'macro_block Err($missing)
             };
@@ -32,6 +33,9 @@ macro_rules! get_req {
             };
     
             $(
+                fn call<T, R>(value: T, function: impl FnOnce(T) -> R) -> R {
+                    (function)(value)
+                }
                 let Ok(value) = $final_map(value) else {
                     break 'macro_block Err(call(value, $final_err))
                 };
@@ -107,14 +111,135 @@ macro_rules! get_opt {
     };
 }
 
-pub fn get_file_from_mapping(mapping: &YamlMapping, base_path: &Path) -> Result<File, FileParseErr> {
-    use super::errors::{ FilePathErr, ContainerTypeErr };
+fn is_glob(path: &Path) -> bool {
+    path.to_str().is_some_and(|path| path.contains(['*', '?', '[']))
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.as_encoded_bytes().starts_with(b".")
+}
+
+/// Collects every file under `dir`. Links to directories aren't followed, so
+/// a link back up the tree can't send this in circles, and dotfiles (`.git`,
+/// `.DS_Store`, ...) are left out unless `include_hidden` is set.
+pub(super) fn walk_dir(base_path: &Path, dir: &Path, include_hidden: bool, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(base_path.join(dir))? {
+        let entry = entry?;
+        if !include_hidden && is_hidden(&entry.file_name()) {
+            continue;
+        }
+        let path = dir.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk_dir(base_path, &path, include_hidden, found)?;
+        } else if !(file_type.is_symlink() && base_path.join(&path).is_dir()) {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Turns a `src` into the paths of every file it refers to, relative to
+/// `base_path`. Globs and directories are expanded and sorted so that the
+/// order doesn't depend on the filesystem.
+fn expand_src(path: PathBuf, base_path: &Path, include_hidden: bool) -> Result<Vec<PathBuf>, FilePathErr> {
+    let display = path.display().to_string();
+    let globbed = is_glob(&path);
+
+    let matched = if globbed {
+        let pattern = base_path
+            .to_str()
+            .map(glob::Pattern::escape)
+            .map(|base| Path::new(&base).join(&path));
+        let Some(pattern) = pattern.as_ref().and_then(|pattern| pattern.to_str()) else {
+            return Err(FilePathErr::BadPath(display));
+        };
+        let options = glob::MatchOptions {
+            require_literal_leading_dot: !include_hidden,
+            ..Default::default()
+        };
+        glob::glob_with(pattern, options)
+            .map_err(|_| FilePathErr::BadPattern(display.clone()))?
+            .filter_map(Result::ok)
+            .filter_map(|matched| matched.strip_prefix(base_path).ok().map(Path::to_path_buf))
+            .collect()
+    } else if base_path.join(&path).is_dir() {
+        vec![path]
+    } else {
+        return Ok(vec![path]);
+    };
+
+    let mut files = vec![];
+    for matched in matched {
+        let full_path = base_path.join(&matched);
+        if !full_path.is_dir() {
+            files.push(matched);
+        } else if !globbed || !full_path.is_symlink() {
+            walk_dir(base_path, &matched, include_hidden, &mut files).map_err(|_| FilePathErr::BadPath(display.clone()))?;
+        }
+    }
+    files.sort();
+
+    if files.is_empty() {
+        Err(FilePathErr::NoMatches(display))
+    } else {
+        Ok(files)
+    }
+}
+
+fn check_contained(base_path: &Path, paths: &[PathBuf], shared_roots: &[PathBuf]) -> Result<(), FilePathErr> {
+    match paths.iter().find(|path| !is_contained(base_path, path, shared_roots)) {
+        Some(path) => Err(FilePathErr::EscapesRoot(path.clone())),
+        None => Ok(()),
+    }
+}
+
+fn read_start(path: &Path, len: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut data = vec![];
+    std::fs::File::open(path)?.take(len as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn check_size(file: &FileSource, size: u64, policy: &FilePolicy) -> Result<(), DataReadErr> {
+    match policy.max_file_size {
+        Some(limit) if size > limit => Err(DataReadErr::TooLarge { file: file.clone(), size, limit }),
+        _ => Ok(()),
+    }
+}
+
+fn check_type(file: &FileSource, data: &[u8], policy: &FilePolicy) -> Result<(), DataReadErr> {
+    let name = match file {
+        FileSource::Local(path) => path.as_path(),
+        FileSource::Remote(url) => Path::new(url.file_name().unwrap_or_default()),
+    };
+    let types = file_types(name, data);
+    match policy.disallowed_type(&types) {
+        Some(file_type) => Err(DataReadErr::DisallowedType { file: file.clone(), file_type: file_type.to_string() }),
+        None => Ok(()),
+    }
+}
+
+pub fn get_file_from_mapping(mapping: &YamlMapping, base_path: &Path, policy: &FilePolicy) -> Result<Vec<File>, FileParseErr> {
+    use super::errors::{ ContainerTypeErr, ArchiveErr, ChecksumErr };
 
-    let path = get_req!(
-        mapping.src else FilePathErr::NoExist;
+    let url = get_opt!(
+        mapping.[url];
         as_str else FilePathErr::NotStr;
-        (PathBuf::from_str) else (|s| FilePathErr::BadPath(s.to_string()));
+        Some(parse_url) else (|s| FilePathErr::BadUrl(s.to_string()));
     );
+    let remote = matches!(url, Ok(Some(_)));
+
+    let source = match (url, mapping.contains_key("src")) {
+        (Ok(Some(_)), true) => Err(FilePathErr::SrcAndUrl),
+        (Ok(Some(url)), false) => Ok(FileSource::Remote(url)),
+        (Err(err), _) => Err(err),
+        (Ok(None), _) => get_req!(
+            mapping.src else FilePathErr::NoExist;
+            as_str else FilePathErr::NotStr;
+        ).map(PathBuf::from).map(FileSource::Local),
+    };
 
     let visible = get_opt!(
         mapping.[vis, visible];
@@ -130,51 +255,202 @@ pub fn get_file_from_mapping(mapping: &YamlMapping, base_path: &Path) -> Result<
         as_str else ContainerTypeErr::NotStr;
         Some(ContainerType::try_from_str) else (|s| ContainerTypeErr::BadType(s.to_string()));
     );
+    let archive = get_opt!(
+        mapping.[archive];
+        as_str else ArchiveErr::NotStr;
+        Some(ArchiveFormat::try_from_str) else (|s| ArchiveErr::BadFormat(s.to_string()));
+    );
+    let include_hidden = get_opt!(
+        mapping.[include_hidden];
+        as_bool else identity;
+    );
+    let hidden = matches!(include_hidden, Ok(Some(true)));
+
+    // Archived directories become a single file, so they aren't expanded
+    let archive = match (&source, archive) {
+        (Ok(source), Ok(Some(_))) if !source.path().is_some_and(|path| base_path.join(path).is_dir()) => {
+            Err(ArchiveErr::NotDirectory(source.clone()))
+        },
+        (_, archive) => archive,
+    };
+    let archiving = matches!(archive, Ok(Some(_)));
+
+    let expected_sha256 = get_opt!(
+        mapping.[sha256];
+        as_str else ChecksumErr::NotStr;
+        Some(parse_sha256) else (|s| ChecksumErr::BadFormat(s.to_string()));
+    );
+
+    let sources = source.and_then(|source| {
+        let path = match source {
+            FileSource::Local(path) if archive.is_ok() => path,
+            source => return Ok(vec![source]),
+        };
+        if archiving {
+            let mut archived = vec![path.clone()];
+            walk_dir(base_path, &path, hidden, &mut archived).map_err(|_| FilePathErr::BadPath(path.display().to_string()))?;
+            check_contained(base_path, &archived, &policy.shared_roots)?;
+            return Ok(vec![FileSource::Local(path)]);
+        }
+        let display = path.display().to_string();
+        let paths = expand_src(path, base_path, hidden)?;
+        check_contained(base_path, &paths, &policy.shared_roots)?;
+        if paths.len() > 1 && matches!(alias, Ok(Some(_))) {
+            Err(FilePathErr::AliasForMany(display, paths.len()))
+        } else if paths.len() > 1 && matches!(expected_sha256, Ok(Some(_))) {
+            Err(FilePathErr::ChecksumForMany(display, paths.len()))
+        } else {
+            Ok(paths.into_iter().map(FileSource::Local).collect())
+        }
+    });
 
     let data = 'data_block: {
-        use super::errors::DataReadErr;
         use std::io::ErrorKind as IoErrorKind;
         use once_cell::sync::OnceCell;
 
-        if matches!(container, Ok(Some(_))) { break 'data_block Ok(OnceCell::new()); }
+        let Ok(sources) = sources.as_ref() else { break 'data_block Ok(vec![]) };
 
-        let path = if let Ok(path) = path.as_ref() {
-            let uncanonicalized = base_path.join(path);
-            match base_path.join(path).canonicalize() {
-                Ok(path) => path,
-                Err(_) => break 'data_block Err(DataReadErr::Canonicalize(uncanonicalized)),
-            }
-        } else { break 'data_block Ok(OnceCell::new()) };
+        if let Some(source @ FileSource::Remote(url)) = sources.first() {
+            let Some(fetcher) = policy.fetcher.as_ref() else {
+                break 'data_block Ok(vec![OnceCell::new()])
+            };
+            break 'data_block match fetcher.0.fetch(url.as_str()) {
+                Ok(data) => check_size(source, data.len() as u64, policy)
+                    .and_then(|_| check_type(source, &data, policy))
+                    .map(|_| vec![OnceCell::with_value(data)]),
+                Err(reason) => Err(DataReadErr::FetchFailed { url: url.to_string(), reason }),
+            };
+        }
 
-        match std::fs::read(&path) {
-            Ok(data) => Ok(OnceCell::with_value(data)),
-            Err(err) => match err.kind() {
-                IoErrorKind::NotFound => Err(DataReadErr::DoesntExist(path)),
-                _ => Err(DataReadErr::OsError(path)),
-            }
+        if let Ok(Some(format)) = archive {
+            let Some(dir) = sources.first().and_then(FileSource::path) else { break 'data_block Ok(vec![]) };
+            let data = match build_archive(base_path, dir, format, hidden) {
+                Ok(data) => data,
+                Err(_) => break 'data_block Err(DataReadErr::OsError(base_path.join(dir))),
+            };
+            let archive_path = FileSource::Local(PathBuf::from(format!("{}.{}", dir.display(), format.to_str())));
+            break 'data_block check_size(&archive_path, data.len() as u64, policy)
+                .and_then(|_| check_type(&archive_path, &data, policy))
+                .map(|_| vec![OnceCell::with_value(data)]);
         }
+
+        // Files copied into a container are only checked against the policy.
+        // Files bundled into the static site get served, so they're kept.
+        let keep = !matches!(container, Ok(Some(cont_type)) if cont_type != ContainerType::Static);
+
+        sources
+            .iter()
+            .filter_map(FileSource::path)
+            .map(|path| {
+                let uncanonicalized = base_path.join(path);
+                let path = match uncanonicalized.canonicalize() {
+                    Ok(path) => path,
+                    Err(_) => return Err(DataReadErr::Canonicalize(uncanonicalized)),
+                };
+
+                // Checked before reading so oversized files never end up in memory
+                let file = FileSource::Local(path.clone());
+                if let Ok(metadata) = std::fs::metadata(&path) {
+                    check_size(&file, metadata.len(), policy)?;
+                }
+
+                let read = if keep {
+                    std::fs::read(&path)
+                } else {
+                    read_start(&path, MAGIC_LEN)
+                };
+                match read {
+                    Ok(data) => {
+                        check_type(&file, &data, policy)?;
+                        Ok(if keep { OnceCell::with_value(data) } else { OnceCell::new() })
+                    },
+                    Err(err) => match err.kind() {
+                        IoErrorKind::NotFound => Err(DataReadErr::DoesntExist(path)),
+                        _ => Err(DataReadErr::OsError(path)),
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
     };
 
-    match (path, visible, alias, container, data) {
+    let digests: Vec<_> = data
+        .as_ref()
+        .map(|cells| cells.iter().map(|cell| cell.get().map(|data| sha256(data))).collect())
+        .unwrap_or_default();
+
+    // Unless they're fetched, remote files are only known by their pinned digest
+    let pinned = expected_sha256.as_ref().ok().copied().flatten();
+
+    let checksum = expected_sha256.and_then(|expected| {
+        if remote && expected.is_none() {
+            return Err(ChecksumErr::MissingForUrl);
+        }
+        let (Some(expected), Ok(sources)) = (expected, sources.as_ref()) else { return Ok(()) };
+        for (source, digest) in sources.iter().zip(&digests) {
+            match digest {
+                None if remote => (),
+                None => return Err(ChecksumErr::Unverifiable),
+                Some(digest) if *digest != expected => return Err(ChecksumErr::Mismatch {
+                    file: source.clone(),
+                    expected: to_hex(&expected),
+                    actual: to_hex(digest),
+                }),
+                Some(_) => (),
+            }
+        }
+        Ok(())
+    });
+
+    match (sources, visible, alias, container, archive, include_hidden, data, checksum) {
         (
-            Ok(path),
+            Ok(sources),
             Ok(visible),
             Ok(alias),
             Ok(container),
+            Ok(archive),
+            Ok(_),
             Ok(data),
-        ) => Ok(File { path, visible: visible.unwrap_or(true), alias, container, data }),
+            Ok(()),
+        ) => Ok(
+            sources
+                .into_iter()
+                .zip(data)
+                .zip(digests)
+                .map(|((source, data), sha256)| {
+                    let sha256 = sha256.or(pinned);
+                    let alias = alias.clone().or_else(|| archive.and_then(
+                        |format| Some(format!("{}.{}", source.path()?.file_name()?.to_str()?, format.to_str()))
+                    ));
+                    File {
+                        source,
+                        visible: visible.unwrap_or(true),
+                        alias,
+                        container,
+                        archive,
+                        data,
+                        sha256,
+                    }
+                })
+                .collect()
+        ),
         (
-            path,
+            sources,
             visible,
             alias,
             container,
+            archive,
+            include_hidden,
             data,
+            checksum,
         ) => Err(FileParseErr::Parts {
-            path: path.err(),
+            path: sources.err(),
             visible: visible.err(),
             alias: alias.err(),
             container: container.err(),
-            data: data.err(),
+            archive: archive.err(),
+            include_hidden: include_hidden.err(),
+            data: data.err().map(Box::new),
+            sha256: checksum.err().map(Box::new),
         })
     }
 }
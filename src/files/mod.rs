@@ -1,21 +1,28 @@
 pub mod structs;
 pub mod errors;
 mod get_file;
+mod archive;
+pub mod names;
+pub mod fetch;
+pub mod file_type;
+pub mod digest;
 
 
 use std::path::Path;
 
-use structs::Files;
+use structs::{File, Files};
 
 
 use serde_yaml::Value as YamlValue;
 
+use crate::correctness::FilePolicy;
+use crate::deploy::structs::{DeployOptions, DeployTargetType, StaticTarget};
 use crate::structs::get_type;
 
 use self::errors::{FileParseErr, FileErrors};
 use self::get_file::get_file_from_mapping;
 
-pub fn file_list(value: &YamlValue, base_path: &Path) -> Result<Files, FileErrors> {
+pub fn file_list(value: &YamlValue, base_path: &Path, policy: &FilePolicy) -> Result<Files, FileErrors> {
     let sequence = value.as_sequence().ok_or_else(|| FileErrors::BadBaseType(get_type(value)))?;
 
     let entries = sequence
@@ -26,6 +33,7 @@ pub fn file_list(value: &YamlValue, base_path: &Path) -> Result<Files, FileError
                     .as_mapping()
                     .ok_or_else(|| FileParseErr::ItemNotMapping(get_type(value)))?,
                 base_path,
+                policy,
             )
         );
 
@@ -34,17 +42,83 @@ pub fn file_list(value: &YamlValue, base_path: &Path) -> Result<Files, FileError
 
     entries.for_each(
         |res| match res {
-            Ok(path) => {
-                files.push(path);
+            Ok(expanded) => {
+                files.extend(expanded);
                 errs.push(None);
             },
             Err(e) => errs.push(Some(e)),
         }
     );
 
-    if files.len() == errs.len() {
-        Ok(Files(files))
+    if !errs.iter().all(Option::is_none) {
+        return Err(FileErrors::EntryErrors(errs));
+    }
+
+    let total_size: u64 = files
+        .iter()
+        .filter_map(File::data)
+        .map(|data| data.len() as u64)
+        .sum();
+    match policy.max_total_size {
+        Some(limit) if total_size > limit => Err(FileErrors::TotalTooLarge { size: total_size, limit }),
+        _ => Ok(Files(files)),
+    }
+}
+
+/// Errors with every file routed to a container that isn't declared under
+/// `deploy`.
+pub fn verify_containers(files: &Files, deploy: Option<&DeployOptions>) -> Result<(), FileErrors> {
+    let undeclared: Vec<_> = files
+        .iter()
+        .filter_map(|file| Some((file, file.container()?)))
+        .filter(|(_, container)| !deploy.is_some_and(|deploy| deploy.has_target(container.target_type())))
+        .map(|(file, container)| (file.source.clone(), container))
+        .collect();
+
+    if undeclared.is_empty() {
+        Ok(())
     } else {
-        Err(FileErrors::EntryErrors(errs))
+        Err(FileErrors::UndeclaredContainers(undeclared))
     }
 }
+
+/// Errors if the flag file gets handed to players, either directly, inside
+/// an archived directory, or as part of the static site's directory.
+pub fn verify_flag_hidden(files: Option<&Files>, static_site: Option<&StaticTarget>, base_path: &Path, flag_path: &Path) -> Result<(), FileErrors> {
+    let Ok(flag) = base_path.join(flag_path).canonicalize() else { return Ok(()) };
+
+    let exposed = files
+        .into_iter()
+        .flat_map(Files::iter)
+        .filter(|file| file.is_served())
+        .find(|file| {
+            let Some(Ok(path)) = file.path().map(|path| base_path.join(path).canonicalize()) else { return false };
+            if file.archive().is_some() {
+                flag.starts_with(path)
+            } else {
+                flag == path
+            }
+        })
+        .and_then(File::path)
+        .map(|path| FileErrors::FlagExposed(path.to_path_buf()));
+    let served = static_site
+        .map(|site| &site.dir)
+        .filter(|dir| base_path.join(dir).canonicalize().is_ok_and(|dir| flag.starts_with(dir)))
+        .map(|dir| FileErrors::FlagServed(dir.clone()));
+
+    match exposed.or(served) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Deploy targets that no file is routed to.
+pub fn unrouted_targets(files: Option<&Files>, deploy: &DeployOptions) -> Vec<DeployTargetType> {
+    DeployTargetType::ALL
+        .into_iter()
+        .filter(|&target| deploy.has_target(target))
+        .filter(|&target| !files.is_some_and(
+            |files| files.iter().any(|file| file.container().map(|c| c.target_type()) == Some(target))
+        ))
+        .collect()
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use super::errors::{DownloadNameErr, NameIssue};
+use super::structs::{FileSource, Files};
+
+
+/// Names Windows reserves for devices, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const MAX_NAME_LEN: usize = 255;
+
+/// Why `name` can't be saved as-is on every common OS, if it can't.
+pub fn portability_issue(name: &str) -> Option<NameIssue> {
+    if name.is_empty() || name == "." || name == ".." {
+        return Some(NameIssue::Empty);
+    }
+    if let Some(c) = name.chars().find(|c| matches!(c, '/' | '\\')) {
+        return Some(NameIssue::Separator(c));
+    }
+    if name.chars().any(char::is_control) {
+        return Some(NameIssue::ControlChar);
+    }
+    if let Some(c) = name.chars().find(|c| matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*')) {
+        return Some(NameIssue::ReservedChar(c));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Some(NameIssue::TrailingDotOrSpace);
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        return Some(NameIssue::ReservedName);
+    }
+    if name.len() > MAX_NAME_LEN {
+        return Some(NameIssue::TooLong(name.len()));
+    }
+    None
+}
+
+/// Checks the names players download files under. They have to be portable,
+/// and unique even on case-insensitive filesystems.
+pub fn verify_download_names(files: &Files) -> Vec<DownloadNameErr> {
+    let mut errors = vec![];
+    let mut seen: HashMap<String, &FileSource> = HashMap::new();
+
+    for file in files.iter().filter(|file| file.is_served()) {
+        let name = file.download_name();
+        if let Some(issue) = portability_issue(&name) {
+            errors.push(DownloadNameErr::Unportable { name: name.to_string(), file: file.source().clone(), issue });
+            continue;
+        }
+        match seen.get(&name.to_lowercase()) {
+            Some(first) => errors.push(DownloadNameErr::Collision {
+                name: name.to_string(),
+                first: (*first).clone(),
+                second: file.source().clone(),
+            }),
+            None => {
+                seen.insert(name.to_lowercase(), file.source());
+            },
+        }
+    }
+
+    errors
+}
@@ -1,12 +1,18 @@
-use std::fmt::Debug;
+use std::borrow::Cow;
+use std::fmt::{Debug, Display};
 use std::path::{PathBuf, Path};
 
+use crate::deploy::structs::DeployTargetType;
+
+use super::digest::{Sha256Digest, to_hex};
+use super::fetch::Url;
+
 
 #[derive(Clone, PartialEq)]
 pub struct Files(pub (super) Vec<File>);
 impl Files {
     pub fn iter_paths(&self) -> impl Iterator<Item = &Path> {
-        self.0.iter().map(|f| f.path.as_path())
+        self.0.iter().filter_map(File::path)
     }
     pub fn iter(&self) -> impl Iterator<Item = &File> {
         self.0.iter()
@@ -41,6 +47,14 @@ impl ContainerType {
             _ => None,
         }
     }
+    pub fn target_type(&self) -> DeployTargetType {
+        match self {
+            ContainerType::Nc => DeployTargetType::Nc,
+            ContainerType::Admin => DeployTargetType::Admin,
+            ContainerType::Web => DeployTargetType::Web,
+            ContainerType::Static => DeployTargetType::Static,
+        }
+    }
     pub fn to_str(&self) -> &'static str {
         match self {
             ContainerType::Nc => "nc",
@@ -57,33 +71,114 @@ impl Debug for ContainerType {
 }
 
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+impl ArchiveFormat {
+    pub fn try_from_str(s: &str) -> Option<Self> {
+        match s {
+            "zip" => Some(ArchiveFormat::Zip),
+            "tar.gz" | "tgz" => Some(ArchiveFormat::TarGz),
+            _ => None,
+        }
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+}
+
+
+/// Where a file comes from: the challenge directory, or somewhere else.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileSource {
+    Local(PathBuf),
+    Remote(Url),
+}
+impl FileSource {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            FileSource::Local(path) => Some(path),
+            FileSource::Remote(_) => None,
+        }
+    }
+    pub fn url(&self) -> Option<&Url> {
+        match self {
+            FileSource::Local(_) => None,
+            FileSource::Remote(url) => Some(url),
+        }
+    }
+}
+impl Display for FileSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileSource::Local(path) => write!(f, "{}", path.display()),
+            FileSource::Remote(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+
 #[derive(Clone, PartialEq)]
 pub struct File {
-    pub (super) path: PathBuf,
+    pub (super) source: FileSource,
     pub (super) visible: bool,
     pub (super) alias: Option<String>,
 
     pub (super) data: once_cell::sync::OnceCell<Vec<u8>>,
+    pub (super) sha256: Option<Sha256Digest>,
 
     pub (super) container: Option<ContainerType>,
+    pub (super) archive: Option<ArchiveFormat>,
 }
 impl File {
-    pub fn path(&self) -> &Path { &self.path }
+    pub fn source(&self) -> &FileSource { &self.source }
+    /// Only files in the challenge directory have a path.
+    pub fn path(&self) -> Option<&Path> { self.source.path() }
     pub fn visible(&self) -> bool { self.visible }
     pub fn alias(&self) -> Option<&str> { self.alias.as_deref() }
     pub fn container(&self) -> Option<ContainerType> { self.container }
+    pub fn archive(&self) -> Option<ArchiveFormat> { self.archive }
+    pub fn url(&self) -> Option<&str> { self.source.url().map(Url::as_str) }
     pub fn data(&self) -> Option<&[u8]> { self.data.get().map(Vec::as_slice) }
+    pub fn sha256(&self) -> Option<&Sha256Digest> { self.sha256.as_ref() }
+    pub fn sha256_hex(&self) -> Option<String> { self.sha256.as_ref().map(|digest| to_hex(digest)) }
     pub fn data_vec(self) -> Option<Vec<u8>> { self.data.into_inner() }
     pub fn data_vec_cloned(&self) -> Option<Vec<u8>> { self.data.get().cloned() }
+
+    /// The name players get the file under: its alias, or else its file name
+    /// (the last segment of the url for files stored elsewhere).
+    pub fn download_name(&self) -> Cow<'_, str> {
+        if let Some(alias) = &self.alias {
+            return Cow::Borrowed(alias);
+        }
+        match &self.source {
+            FileSource::Local(path) => path.file_name().map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy()),
+            FileSource::Remote(url) => Cow::Borrowed(url.file_name().unwrap_or(url.as_str())),
+        }
+    }
+
+    /// Whether players can get at the file. Files routed into a container
+    /// other than the static site never leave it.
+    pub fn is_served(&self) -> bool {
+        self.visible && self.container.is_none_or(|container| container == ContainerType::Static)
+    }
 }
 impl Debug for File {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "File< ")?;
         
         if let Some(alias) = self.alias.as_ref() {
-            write!(f, "@{} => {alias}", self.path.display())?;
+            write!(f, "@{} => {alias}", self.source)?;
         } else {
-            write!(f, "@{}", self.path.display())?;
+            write!(f, "@{}", self.source)?;
+        }
+        if let Some(archive) = self.archive {
+            write!(f, " ({})", archive.to_str())?;
         }
         write!(f, " | ")?;
 
@@ -1,11 +1,15 @@
-use std::{fmt::{Display, Debug}, path::{PathBuf, Path}, str::FromStr, io::ErrorKind};
+use std::{fmt::{Display, Debug}, path::{PathBuf, Path}, io::ErrorKind};
 
 use serde_yaml::Value as YamlValue;
 
+use crate::paths::is_contained;
 use crate::structs::{get_type, ValueType};
 
 
-pub fn get_file_flag(path: PathBuf, base_path: &Path) -> Result<Flag, FlagError> {
+pub fn get_file_flag(path: PathBuf, base_path: &Path, shared_roots: &[PathBuf]) -> Result<Flag, FlagError> {
+    if !is_contained(base_path, &path, shared_roots) {
+        return Err(FlagError::EscapesRoot(path));
+    }
     match std::fs::read_to_string(base_path.join(&path)) {
         Ok(s) => Ok(Flag::File(path, s)),
         Err(e) => if e.kind() == ErrorKind::NotFound {
@@ -16,17 +20,14 @@ pub fn get_file_flag(path: PathBuf, base_path: &Path) -> Result<Flag, FlagError>
     }
 }
 
-pub fn get_flag(value: &YamlValue, base_path: &Path) -> Result<Flag, FlagError> {
+pub fn get_flag(value: &YamlValue, base_path: &Path, shared_roots: &[PathBuf]) -> Result<Flag, FlagError> {
     if let Some(flag_str) = value.as_str() {
         Ok(Flag::String(flag_str.trim().to_string()))
     } else if let Some(mapping) = value.as_mapping() {
         if let Some(Some(file)) = mapping.get("file").map(YamlValue::as_str) {
-            if let Ok(path) = PathBuf::from_str(file) {
-                if path.is_relative() {
-                    get_file_flag(path, base_path)
-                } else {
-                    Err(FlagError::BadPath(file.to_string()))
-                }
+            let path = PathBuf::from(file);
+            if path.is_relative() {
+                get_file_flag(path, base_path, shared_roots)
             } else {
                 Err(FlagError::BadPath(file.to_string()))
             }
@@ -77,6 +78,7 @@ pub enum FlagError {
     MappingNeedsFile,
     FileMissing(PathBuf),
     OsError(PathBuf),
+    EscapesRoot(PathBuf),
     
     #[default]
     MissingKey,
@@ -93,6 +95,7 @@ impl Display for FlagError {
             MissingKey => write!(f, "You have to define `categories`."),
             FileMissing(p) => write!(f, "There is no file at {}.", p.display()),
             OsError(p) => write!(f, "There was an issue opening the file at {}. Maybe check permissions?", p.display()),
+            EscapesRoot(p) => write!(f, "The flag file {} is outside of the challenge directory.", p.display()),
         }
     }
 }
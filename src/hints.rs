@@ -0,0 +1,283 @@
+use std::fmt::{Debug, Display};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_yaml::{Mapping as YamlMapping, Value as YamlValue};
+
+use crate::structs::{get_type, ValueType};
+use crate::Flop;
+
+
+/// `2024-06-01T12:00:00Z`, `2024-06-01 12:00-04:00`, ...
+static RELEASE_TIME: Lazy<Regex> = Lazy::new(
+    || Regex::new(r"^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:\d{2})$").unwrap()
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HintUnlock {
+    /// Only after the hint at this index has been unlocked.
+    AfterHint(usize),
+    /// Only once the challenge has been out for this long.
+    AfterTime(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub text: String,
+    pub cost: u64,
+    pub unlock: Option<HintUnlock>,
+    pub hidden: bool,
+    pub release: Option<String>,
+}
+impl Hint {
+    fn from_text(text: &str) -> Self {
+        Self { text: text.to_string(), cost: 0, unlock: None, hidden: false, release: None }
+    }
+}
+
+#[derive(Default, Clone, PartialEq)]
+pub struct Hints(Vec<Hint>);
+impl Hints {
+    pub fn iter(&self) -> impl Iterator<Item = &Hint> {
+        self.0.iter()
+    }
+    pub fn slice(&self) -> &[Hint] {
+        &self.0
+    }
+    pub fn total_cost(&self) -> u64 {
+        self.0.iter().map(|hint| hint.cost).sum()
+    }
+}
+impl Debug for Hints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Hints ")?;
+        f.debug_list()
+            .entries(self.0.iter())
+            .finish()
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HintEntryError {
+    BadType(ValueType),
+    Parts {
+        text: Option<ValueType>,
+        cost_invalid: Option<ValueType>,
+        requires: Option<RequiresError>,
+        unlock_after_invalid: Option<ValueType>,
+        hidden_invalid: Option<ValueType>,
+        release: Option<ReleaseError>,
+    },
+    BothUnlocks,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequiresError {
+    NotIndex(ValueType),
+    NotEarlier(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReleaseError {
+    NotStr(ValueType),
+    BadFormat(String),
+}
+
+#[derive(Default, Debug, Clone)]
+pub enum HintError {
+    BadType(ValueType),
+    Entries(Vec<Option<HintEntryError>>),
+    CostsExceedPoints {
+        total: u64,
+        points: u64,
+    },
+    #[default]
+    MissingKey,
+}
+
+impl Display for RequiresError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotIndex(t) => write!(f, "`requires` should be the index of another hint, not {t}."),
+            Self::NotEarlier(idx) => write!(f, "`requires` can only point to an earlier hint, and hint {idx} isn't."),
+        }
+    }
+}
+
+impl Display for ReleaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotStr(t) => write!(f, "`release` should be a timestamp, not {t}."),
+            Self::BadFormat(s) => write!(f, "`release` should be a timestamp like `2024-06-01T12:00:00Z`. \"{s}\" is not."),
+        }
+    }
+}
+
+impl Display for HintEntryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadType(t) => write!(f, "A hint should be a string or a map with `text`, not {t}."),
+            Self::BothUnlocks => write!(f, "A hint can unlock after another hint (`requires`) or after some time (`unlock_after`), not both."),
+            Self::Parts {
+                text,
+                cost_invalid,
+                requires,
+                unlock_after_invalid,
+                hidden_invalid,
+                release,
+            } => {
+                writeln!(f, "There were issues with certain parts of this hint:")?;
+                if let Some(text) = text {
+                    writeln!(f, "            The hint's `text` must be a string, not {text}")?;
+                }
+                if let Some(cost) = cost_invalid {
+                    writeln!(f, "            The `cost` of a hint must be a positive integer, not {cost}")?;
+                }
+                if let Some(requires) = requires {
+                    writeln!(f, "            {requires}")?;
+                }
+                if let Some(unlock_after) = unlock_after_invalid {
+                    writeln!(f, "            `unlock_after` must be a number of seconds, not {unlock_after}")?;
+                }
+                if let Some(hidden) = hidden_invalid {
+                    writeln!(f, "            `hidden` must be a boolean, not {hidden}")?;
+                }
+                if let Some(release) = release {
+                    writeln!(f, "            {release}")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Display for HintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use HintError::*;
+        match self {
+            BadType(t) => writeln!(f, "Hints should be in a list, not {t}."),
+            Entries(errs) => {
+                writeln!(f, "Some hints are invalid:")?;
+                let iter = errs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(idx, err)| err.as_ref().map(|e| (idx, e)));
+
+                for (idx, err) in iter {
+                    writeln!(f, "        {idx}: {err}")?;
+                }
+                Ok(())
+            },
+            CostsExceedPoints { total, points } => writeln!(f, "The hints cost {total} points in total, which is more than the challenge's {points}."),
+            MissingKey => writeln!(f, "You have to define `hints`."),
+        }
+    }
+}
+
+
+fn parse_hint_mapping(mapping: &YamlMapping, idx: usize) -> Result<Hint, HintEntryError> {
+    let text = match mapping.get("text") {
+        Some(value) => value.as_str().map(str::to_string).ok_or_else(|| get_type(value)),
+        None => Err(ValueType::NULL),
+    };
+
+    let cost = mapping.get("cost").map(
+        |value| value.as_u64().ok_or_else(|| get_type(value))
+    ).flop();
+
+    let requires = mapping.get("requires").map(|value| {
+        let required = value.as_u64().ok_or_else(|| RequiresError::NotIndex(get_type(value)))?;
+        if required < idx as u64 {
+            Ok(required as usize)
+        } else {
+            Err(RequiresError::NotEarlier(required))
+        }
+    }).flop();
+
+    let unlock_after = mapping.get("unlock_after").map(
+        |value| value.as_u64().map(Duration::from_secs).ok_or_else(|| get_type(value))
+    ).flop();
+
+    let hidden = mapping.get("hidden").map(
+        |value| value.as_bool().ok_or_else(|| get_type(value))
+    ).flop();
+
+    let release = mapping.get("release").map(|value| {
+        let release = value.as_str().ok_or_else(|| ReleaseError::NotStr(get_type(value)))?;
+        if RELEASE_TIME.is_match(release) {
+            Ok(release.to_string())
+        } else {
+            Err(ReleaseError::BadFormat(release.to_string()))
+        }
+    }).flop();
+
+    match (text, cost, requires, unlock_after, hidden, release) {
+        (Ok(text), Ok(cost), Ok(requires), Ok(unlock_after), Ok(hidden), Ok(release)) => {
+            let unlock = match (requires, unlock_after) {
+                (Some(_), Some(_)) => return Err(HintEntryError::BothUnlocks),
+                (Some(idx), None) => Some(HintUnlock::AfterHint(idx)),
+                (None, Some(time)) => Some(HintUnlock::AfterTime(time)),
+                (None, None) => None,
+            };
+            Ok(Hint {
+                text,
+                cost: cost.unwrap_or(0),
+                unlock,
+                hidden: hidden.unwrap_or(false),
+                release,
+            })
+        },
+        (text, cost, requires, unlock_after, hidden, release) => Err(HintEntryError::Parts {
+            text: text.err(),
+            cost_invalid: cost.err(),
+            requires: requires.err(),
+            unlock_after_invalid: unlock_after.err(),
+            hidden_invalid: hidden.err(),
+            release: release.err(),
+        }),
+    }
+}
+
+/// Hints are either plain strings, which are free, or maps with `text` and
+/// optionally `cost`, `requires`/`unlock_after`, `hidden` and `release`.
+pub fn parse_hints(value: &YamlValue) -> Result<Hints, HintError> {
+    let sequence = value.as_sequence().ok_or_else(|| HintError::BadType(get_type(value)))?;
+
+    let mut hints = vec![];
+    let mut errs = vec![];
+
+    for (idx, entry) in sequence.iter().enumerate() {
+        let hint = if let Some(text) = entry.as_str() {
+            Ok(Hint::from_text(text))
+        } else if let Some(mapping) = entry.as_mapping() {
+            parse_hint_mapping(mapping, idx)
+        } else {
+            Err(HintEntryError::BadType(get_type(entry)))
+        };
+
+        match hint {
+            Ok(hint) => {
+                hints.push(hint);
+                errs.push(None);
+            },
+            Err(err) => errs.push(Some(err)),
+        }
+    }
+
+    if errs.iter().all(Option::is_none) {
+        Ok(Hints(hints))
+    } else {
+        Err(HintError::Entries(errs))
+    }
+}
+
+pub fn verify_hint_costs(hints: &Hints, points: u64) -> Result<(), HintError> {
+    let total = hints.total_cost();
+    if total > points {
+        Err(HintError::CostsExceedPoints { total, points })
+    } else {
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 pub mod categories;
 pub mod lists;
 pub mod flag;
+pub mod description;
 pub mod files;
 pub mod deploy;
 
@@ -9,6 +10,10 @@ mod accessors;
 mod serialize_impl;
 
 pub mod correctness;
+pub mod template;
+pub mod markdown;
+pub mod hints;
+pub mod paths;
 
 #[cfg(test)]
 pub mod tests;
@@ -24,20 +29,35 @@ use serde_yaml::{
 
 // Parsing functions, types, and errors
 use {
-    categories::value_to_categories,
+    categories::{value_to_categories, primary_to_categories},
     flag::get_flag,
-    files::file_list,
-    lists::as_str_list,
+    description::{get_description, DescriptionError},
+    files::{file_list, verify_containers, verify_flag_hidden, unrouted_targets},
+    files::names::verify_download_names,
+    lists::{as_str_list, as_author_list},
+    hints::{parse_hints, verify_hint_costs},
     deploy::parse_deploy,
+    deploy::dockerfile::inspect_deploy,
+    deploy::admin_bot::{parse_admin_bot, verify_admin_bot},
+    template::verify_template,
+    markdown::check_description,
 };
 
 use {
     files::structs::Files,
     flag::Flag,
-    lists::structs::{ Authors, Hints },
+    description::Description,
+    lists::structs::{ Authors, Tags },
+    hints::Hints,
     categories::Categories,
 };
+pub use categories::DEFAULT_CATEGORY_DEFS;
+
+/// The ids of [`DEFAULT_CATEGORY_DEFS`], in the format of the `CATEGORIES` variable.
+pub const DEFAULT_CATEGORIES: &str = "misc,binex,foren,crypto,webex,rev";
 pub use deploy::structs::DeployOptions;
+pub use deploy::admin_bot::AdminBot;
+use deploy::error::DeployOptionsError;
 pub use files::structs::File;
 
 // Yaml ValueType stuff
@@ -50,12 +70,10 @@ use structs::{
 // Verification stuff
 pub use structs::{
     YamlVerifyError,
-    YamlAttribVerifyError
+    YamlAttribVerifyError,
+    YamlVerifyWarning,
 };
-use correctness::YamlCorrectness;
-
-
-pub const DEFAULT_CATEGORIES: &str = "misc,binex,foren,crypto,webex,rev";
+use correctness::{KeyRequirement, YamlCorrectness};
 
 
 
@@ -64,17 +82,22 @@ pub struct YamlShape {
     authors: Authors,
     categories: Categories,
     hints: Hints,
+    tags: Tags,
     files: Option<Files>,
 
     deploy: Option<DeployOptions>,
+    admin_bot: Option<AdminBot>,
 
     points: u64,
+    difficulty: Option<String>,
     flag: Flag,
     
     name: String,
-    description: String,
+    description: Description,
 
     visible: bool,
+
+    warnings: Vec<YamlVerifyWarning>,
 }
 
 
@@ -108,6 +131,13 @@ macro_rules! get_map {
             .get(stringify!($key))
             .map_or_else(|| Err(Default::default()), $map)
     };
+    ($base:ident.$key:ident, $map:expr, requirement: $requirement:expr $(,)?) => {
+        match $base.get(stringify!($key)) {
+            Some(value) => ($map)(value).map(Some),
+            None if $requirement == KeyRequirement::Optional => Ok(None),
+            None => Err(Default::default()),
+        }
+    };
     ($base:ident.$key:ident, $map:expr, missing: $err:expr, error_wrap: $err_mapper:expr $(,)?) => {
         $base
             .get(stringify!($key))
@@ -130,6 +160,7 @@ fn verify_yaml(yaml_text: &str, correctness_options: Option<YamlCorrectness>, ba
     use YamlAttribVerifyError as AttribError;
 
     let correctness = correctness_options.unwrap_or_default();
+    let required = *correctness.required();
 
     let base: YamlValue = serde_yaml::from_str(yaml_text).map_err(Unparsable)?;
     let base: &YamlMapping = if let Some(base) = base.as_mapping() { base } else {
@@ -140,86 +171,184 @@ fn verify_yaml(yaml_text: &str, correctness_options: Option<YamlCorrectness>, ba
         categories,
         authors,
         hints,
+        tags,
         files,
     ) = {
-        let categories = get_map!(
-            base.categories, value_to_categories,
-            default,
-        ).map_err(AttribError::Categories);
+        let categories = if let Some(primary) = base.get("category") {
+            primary_to_categories(primary, base.get("categories"), correctness.categories())
+        } else {
+            get_map!(
+                base.categories, |value| value_to_categories(value, correctness.categories()),
+                default,
+            )
+        }.map_err(AttribError::Categories);
     
         let authors = get_map!(
-            base.authors, as_str_list,
-            default,
-        ).map_err(AttribError::Authors);
+            base.authors, |value| as_author_list(value, correctness.authors()),
+            requirement: required.authors,
+        ).map(Option::unwrap_or_default).map_err(AttribError::Authors);
 
         let hints = get_map!(
-            base.hints, as_str_list,
-        ).map_err(AttribError::Hints);
-
-        let files = base.get("files")
-            .map(|value| file_list(value, base_path)).flop()
-            .map_err(Files);
+            base.hints, parse_hints,
+            requirement: required.hints,
+        ).map(Option::unwrap_or_default).map_err(AttribError::Hints);
+
+        let tags = base.get("tags")
+            .map(as_str_list).flop()
+            .map(Option::unwrap_or_default)
+            .map_err(AttribError::Tags);
+
+        let files = get_map!(
+            base.files, |value| file_list(value, base_path, correctness.file_policy()),
+            requirement: required.files,
+        ).map_err(Files);
         
-        (categories, authors, hints, files)
+        (categories, authors, hints, tags, files)
     };
 
 
-    let deploy = base
-        .get("deploy")
-        .map(parse_deploy)
-        .flop()
-        .map_err(Deploy);
+    let deploy = get_map!(
+        base.deploy, |value| parse_deploy(value, base_path, &correctness.file_policy().shared_roots),
+        requirement: required.deploy,
+    ).map_err(Deploy);
 
 
     let points = get_primitive!(base.value (as_u64) else PointsNotInt);
+    let difficulty = base
+        .get("difficulty")
+        .map(|value| value.as_str().map(str::to_string).ok_or_else(|| DifficultyNotString(get_type(value))))
+        .flop();
 
     let flag = get_map!(
-        base.flag, |value| get_flag(value, base_path),
+        base.flag, |value| get_flag(value, base_path, &correctness.file_policy().shared_roots),
         default,
     ).map_err(AttribError::Flag);
     
     let name = get_primitive!(base.name (as_str => str::to_string) else NameNotString);
-    let description = get_primitive!(base.description (as_str => str::to_string) else DescNotString);
+    let description = get_map!(
+        base.description, |value| get_description(value, base_path, &correctness.file_policy().shared_roots),
+        missing: DescriptionError::BadType(ValueType::NULL),
+    ).map_err(|err| match err {
+        DescriptionError::BadType(vtype) => DescNotString(vtype),
+        err => AttribError::Description(err),
+    });
     let visible = get_primitive!(base.visible (as_bool) else VisNotBool);
 
+    // `collect_errors!` has a match arm for every mix of errors, so it's
+    // split in two instead of growing past what's reasonable to compile.
     let (
-        authors,
-        categories,
-        hints,
-        files,
-        
-        deploy,
+        (
+            authors,
+            categories,
+            hints,
+            tags,
+            files,
+            
+            deploy,
+        ),
+        (
+            points,
+            difficulty,
+            flag,
+            
+            name,
+            description,
+
+            visible,
+        ),
+    ) = match (
+        collect_errors!(authors, categories, hints, tags, files, deploy),
+        collect_errors!(points, difficulty, flag, name, description, visible),
+    ) {
+        (Ok(attributes), Ok(scalars)) => (attributes, scalars),
+        (attributes, scalars) => return Err(PartErrors(
+            attributes.err().into_iter().chain(scalars.err()).flatten().collect()
+        )),
+    };
 
-        points,
-        flag,
-        
-        name,
-        description,
+    let mut errors = vec![];
 
-        visible,
-    ) = collect_errors!(
-        authors,
-        categories,
-        hints,
-        files,
-        
-        deploy,
+    if let Err(err) = verify_hint_costs(&hints, points) {
+        errors.push(AttribError::Hints(err));
+    }
+
+    let admin_bot = base
+        .get("admin_bot")
+        .map(|value| parse_admin_bot(value, &flag))
+        .flop()
+        .and_then(|admin_bot| {
+            if let Some(admin_bot) = &admin_bot {
+                verify_admin_bot(admin_bot, deploy.as_ref())?;
+            }
+            Ok(admin_bot)
+        })
+        .unwrap_or_else(|err| {
+            errors.push(AttribError::AdminBot(err));
+            None
+        });
+
+    if let Some(flag_path) = flag.path() {
+        let static_site = deploy.as_ref().and_then(|deploy| deploy.static_site.as_ref());
+        if let Err(err) = verify_flag_hidden(files.as_ref(), static_site, base_path, flag_path) {
+            errors.push(Files(err));
+        }
+    }
+    if let Some(files) = &files {
+        if let Err(err) = verify_containers(files, deploy.as_ref()) {
+            errors.push(Files(err));
+        }
+        let name_errors = verify_download_names(files);
+        if !name_errors.is_empty() {
+            errors.push(Files(files::errors::FileErrors::DownloadNames(name_errors)));
+        }
+    }
 
-        points,
-        flag,
+    let file_slice = files.as_ref().map(files::structs::Files::slice);
+    errors.extend(
+        std::iter::once(description.as_str())
+            .chain(hints.iter().map(|hint| hint.text.as_str()))
+            .flat_map(|text| verify_template(text, deploy.as_ref(), file_slice))
+            .map(Template)
+    );
+
+    if let correctness::MarkdownCorrectness::Checked { allowed_html } = correctness.markdown() {
+        errors.extend(
+            check_description(description.as_str(), file_slice, allowed_html)
+                .into_iter()
+                .map(Markdown)
+        );
+    }
 
-        name,
-        description,
-        
-        visible,
-    ).map_err(PartErrors)?;
+    let mut warnings = vec![];
+
+    if let Some(deploy) = &deploy {
+        let (dockerfile_errors, dockerfile_warnings) = inspect_deploy(deploy, base_path, flag.path(), correctness.base_images());
+        if !dockerfile_errors.is_empty() {
+            errors.push(Deploy(DeployOptionsError::Dockerfiles(dockerfile_errors)));
+        }
+        warnings.extend(
+            dockerfile_warnings
+                .into_iter()
+                .map(|(target, warning)| YamlVerifyWarning::Dockerfile(target, warning))
+        );
+        warnings.extend(
+            unrouted_targets(files.as_ref(), deploy)
+                .into_iter()
+                .map(YamlVerifyWarning::NoFilesRouted)
+        );
+    }
+
+    if !errors.is_empty() {
+        return Err(PartErrors(errors));
+    }
 
     let shape = YamlShape {
-        authors, categories, hints, files,
-        deploy,
-        points, flag,
+        authors, categories, hints, tags, files,
+        deploy, admin_bot,
+        points, difficulty, flag,
         name, description,
         visible,
+        warnings,
     };
     correctness.verify(&shape).map_err(Correctness)?;
 
@@ -233,11 +362,31 @@ pub mod __main {
     use std::sync::atomic::AtomicBool;
 
     use crate::correctness::YamlCorrectness;
+    use crate::deploy::k8s::{K8sConfig, manifests_to_yaml};
     use crate::YamlShape;
 
-    pub fn main(yaml_correctness: &YamlCorrectness) {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Command { Verify, Compose, K8s }
+    impl Command {
+        fn from_arg(arg: &str) -> Option<Self> {
+            match arg {
+                "compose" => Some(Self::Compose),
+                "k8s" => Some(Self::K8s),
+                _ => None,
+            }
+        }
+    }
+
+    pub fn main(yaml_correctness: &YamlCorrectness, k8s_config: &K8sConfig) {
         let errors_encountered = AtomicBool::new(false);
 
+        let mut args = std::env::args().skip(1).peekable();
+        let command = args.peek().and_then(|arg| Command::from_arg(arg));
+        if command.is_some() {
+            args.next();
+        }
+        let command = command.unwrap_or(Command::Verify);
+
         macro_rules! set_err_if {
             ($result:expr; $err_ctr:ident: CL ($err_print_stmt:expr); $($mapper:expr)?) => {
                 match ($result) {
@@ -261,14 +410,18 @@ pub mod __main {
             }};
         }
 
-        std::env::args()
-            .skip(1)
+        args
             .filter_map(|path| set_err_if!(
                 PathBuf::from_str(&path);
                 errors_encountered: println!("`{path}` is not a valid path!");
             ))
             .filter_map(|mut path| {
-                println!("{:-^40}", path.display());
+                // Generated files go to stdout, so keep it free of anything else
+                if command == Command::Verify {
+                    println!("{:-^40}", path.display());
+                } else {
+                    eprintln!("{:-^40}", path.display());
+                }
                 set_err_if!(
                     std::fs::read_to_string(&path);
                     errors_encountered: println!("Failed to read `{}` to string. Check location, permissions, and encoding of the file.", path.display());
@@ -282,7 +435,26 @@ pub mod __main {
                 set_err_if!(
                     YamlShape::try_from_str(&data, &yaml_correctness.clone(), Some(&base_path));
                     errors_encountered: CL (|err| eprintln!("{err}"));
-                    |yaml| println!("{yaml:#?}")   
+                    |yaml: YamlShape| {
+                        for warning in yaml.warnings() {
+                            eprint!("warning: {warning}");
+                        }
+                        match command {
+                            Command::Verify => println!("{yaml:#?}"),
+                            Command::Compose => if let Some(compose) = yaml.compose() {
+                                print!("{}", compose.to_yaml());
+                            } else {
+                                eprintln!("`{}` has no deploy targets to generate a compose file from.", yaml.chall_name());
+                                errors_encountered.store(true, core::sync::atomic::Ordering::SeqCst);
+                            },
+                            Command::K8s => if let Some(manifests) = yaml.k8s_manifests(k8s_config) {
+                                print!("{}", manifests_to_yaml(&manifests));
+                            } else {
+                                eprintln!("`{}` has no deploy targets to generate manifests from.", yaml.chall_name());
+                                errors_encountered.store(true, core::sync::atomic::Ordering::SeqCst);
+                            },
+                        }
+                    }
                 );
             });
         if errors_encountered.load(core::sync::atomic::Ordering::SeqCst) {
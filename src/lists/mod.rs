@@ -2,8 +2,11 @@ pub mod structs;
 
 use serde_yaml::Value as YamlValue;
 
+use crate::correctness::AuthorCorrectness;
 use crate::structs::{ValueType, get_type};
 
+use self::structs::{Author, AuthorEntryError, AuthorError, AuthorFieldError, AuthorRole, Authors};
+
 pub trait StrList
 where Self: Sized {
     type Error; 
@@ -40,3 +43,115 @@ pub fn as_str_list<T: StrList>(value: &YamlValue) -> Result<T, T::Error> {
         }
     } else { unreachable!() }
 }
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row.push(substitution.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+fn author_field(
+    mapping: &serde_yaml::Mapping,
+    key: &str,
+    is_valid: impl Fn(&str) -> bool,
+) -> Result<Option<String>, AuthorFieldError> {
+    let Some(value) = mapping.get(key) else { return Ok(None) };
+    let string = value.as_str().ok_or_else(|| AuthorFieldError::BadType(get_type(value)))?;
+    if is_valid(string) {
+        Ok(Some(string.to_string()))
+    } else {
+        Err(AuthorFieldError::BadValue(string.to_string()))
+    }
+}
+
+fn parse_author_mapping(mapping: &serde_yaml::Mapping) -> Result<Author, AuthorEntryError> {
+    let name = author_field(mapping, "name", |name| !name.trim().is_empty())
+        .and_then(|name| name.ok_or(AuthorFieldError::BadType(ValueType::NULL)));
+    let handle = author_field(mapping, "handle", |handle| !handle.is_empty() && !handle.contains(char::is_whitespace));
+    let email = author_field(mapping, "email", |email| {
+        let Some((user, domain)) = email.split_once('@') else { return false };
+        !user.is_empty() && domain.contains('.') && !domain.contains('@') && !email.contains(char::is_whitespace)
+    });
+    let discord = author_field(mapping, "discord", |discord| {
+        (2..=32).contains(&discord.len())
+            && discord.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+    });
+    let role = author_field(mapping, "role", |role| AuthorRole::try_from_str(role).is_some())
+        .map(|role| role.and_then(|role| AuthorRole::try_from_str(&role)));
+
+    match (name, handle, email, discord, role) {
+        (Ok(name), Ok(handle), Ok(email), Ok(discord), Ok(role)) => Ok(Author {
+            name,
+            handle,
+            email,
+            discord,
+            role: role.unwrap_or_default(),
+        }),
+        (name, handle, email, discord, role) => Err(AuthorEntryError::Parts {
+            name: name.err(),
+            handle: handle.err(),
+            email: email.err(),
+            discord: discord.err(),
+            role: role.err(),
+        }),
+    }
+}
+
+/// Authors are either plain names or maps with a `name` and optionally a
+/// `handle`, `email`, `discord` and `role`. With a registry, every author's
+/// handle has to be in it.
+pub fn as_author_list(value: &YamlValue, registry: &AuthorCorrectness) -> Result<Authors, AuthorError> {
+    let sequence = value.as_sequence().ok_or_else(|| AuthorError::BadType(get_type(value)))?;
+
+    let bad_types: Vec<_> = sequence
+        .iter()
+        .filter(|entry| !entry.is_string() && !entry.is_mapping())
+        .map(get_type)
+        .collect();
+    if !bad_types.is_empty() {
+        return Err(AuthorError::BadEntryType(bad_types));
+    }
+
+    let entries: Vec<_> = sequence
+        .iter()
+        .map(|entry| match (entry.as_str(), entry.as_mapping()) {
+            (Some(name), _) => Ok(Author::from_name(name)),
+            (_, Some(mapping)) => parse_author_mapping(mapping),
+            _ => Err(AuthorEntryError::BadType(get_type(entry))),
+        })
+        .collect();
+    if entries.iter().any(Result::is_err) {
+        return Err(AuthorError::Entries(entries.into_iter().map(Result::err).collect()));
+    }
+    let authors: Vec<_> = entries.into_iter().filter_map(Result::ok).collect();
+
+    if let AuthorCorrectness::Registry(registered) = registry {
+        let unregistered: Vec<_> = authors
+            .iter()
+            .map(Author::handle)
+            .filter(|handle| !registered.iter().any(|known| known.eq_ignore_ascii_case(handle)))
+            .map(|handle| {
+                let suggestion = registered
+                    .iter()
+                    .map(|known| (edit_distance(&known.to_lowercase(), &handle.to_lowercase()), known))
+                    .filter(|(distance, _)| *distance <= 2)
+                    .min_by_key(|(distance, _)| *distance)
+                    .map(|(_, known)| known.to_string());
+                (handle.to_string(), suggestion)
+            })
+            .collect();
+        if !unregistered.is_empty() {
+            return Err(AuthorError::Unregistered(unregistered));
+        }
+    }
+
+    Ok(Authors(authors))
+}
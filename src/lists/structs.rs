@@ -4,40 +4,136 @@ use crate::structs::ValueType;
 use super::StrList;
 
 
-#[derive(Default, Clone, PartialEq)]
-pub struct Authors(Vec<String>);
-
-#[derive(Default, Debug, Clone)]
-pub enum AuthorError { BadEntryType(Vec<ValueType>), BadType(ValueType), #[default] MissingKey }
-impl StrList for Authors {
-    type Error = AuthorError; 
-    fn from_iter<'a>(iter: impl Iterator<Item = &'a str>) -> Result<Self, Self::Error> {
-        Ok(Authors(iter.map(str::to_string).collect()))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthorRole { #[default] Author, Tester, Infra }
+impl AuthorRole {
+    pub fn try_from_str(role: &str) -> Option<Self> {
+        match role.to_ascii_lowercase().as_str() {
+            "author" => Some(Self::Author),
+            "tester" => Some(Self::Tester),
+            "infra" => Some(Self::Infra),
+            _ => None,
+        }
     }
-
-    fn from_value_mismatch(iter: impl Iterator<Item = ValueType>) -> Self::Error {
-        AuthorError::BadEntryType(iter.collect())
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::Tester => "tester",
+            Self::Infra => "infra",
+        }
     }
+}
 
-    fn not_sequence(type_enum: ValueType) -> Self::Error {
-        AuthorError::BadType(type_enum)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Author {
+    pub name: String,
+    pub handle: Option<String>,
+    pub email: Option<String>,
+    pub discord: Option<String>,
+    pub role: AuthorRole,
+}
+impl Author {
+    pub fn from_name(name: &str) -> Self {
+        Self { name: name.to_string(), ..Self::default() }
+    }
+    /// The handle, or the name for authors given as a plain string.
+    pub fn handle(&self) -> &str {
+        self.handle.as_deref().unwrap_or(&self.name)
     }
 }
+
+#[derive(Default, Clone, PartialEq)]
+pub struct Authors(pub (super) Vec<Author>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthorFieldError { BadType(ValueType), BadValue(String) }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthorEntryError {
+    BadType(ValueType),
+    Parts {
+        name: Option<AuthorFieldError>,
+        handle: Option<AuthorFieldError>,
+        email: Option<AuthorFieldError>,
+        discord: Option<AuthorFieldError>,
+        role: Option<AuthorFieldError>,
+    },
+}
+
+#[derive(Default, Debug, Clone)]
+pub enum AuthorError {
+    BadEntryType(Vec<ValueType>),
+    BadType(ValueType),
+    Entries(Vec<Option<AuthorEntryError>>),
+    Unregistered(Vec<(String, Option<String>)>),
+    #[default]
+    MissingKey,
+}
 impl Authors {
     pub fn iter(&self) -> impl Iterator<Item = &str> {
-        self.0.iter().map(String::as_str)
+        self.0.iter().map(|author| author.name.as_str())
     }
-    pub fn slice(&self) -> &[String] {
+    pub fn slice(&self) -> &[Author] {
         &self.0
     }
 }
 
+impl Display for AuthorFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadType(t) => write!(f, "should be a string, not {t}"),
+            Self::BadValue(s) => write!(f, "\"{s}\" isn't valid"),
+        }
+    }
+}
+impl Display for AuthorEntryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadType(t) => write!(f, "An author should be a name or a map with `name`, not {t}."),
+            Self::Parts { name, handle, email, discord, role } => {
+                writeln!(f, "There were issues with certain parts of this author:")?;
+                let fields = [("name", name), ("handle", handle), ("email", email), ("discord", discord), ("role", role)];
+                for (field, err) in fields {
+                    if let Some(err) = err {
+                        writeln!(f, "            `{field}` {err}")?;
+                    }
+                }
+                if matches!(role, Some(AuthorFieldError::BadValue(_))) {
+                    writeln!(f, "            (roles are `author`, `tester` and `infra`)")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
 impl Display for AuthorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use AuthorError::*;
         match self {
-            &BadEntryType(_) => writeln!(f, "Author names must be strings."),
+            BadEntryType(_) => writeln!(f, "Each author must be a name, or a map with a `name` (and optionally a `handle`, `email`, `discord` and `role`)."),
             &BadType(t) => writeln!(f, "Authors should be a list, not {t}."),
+            Entries(errs) => {
+                writeln!(f, "Some authors are invalid:")?;
+                let iter = errs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(idx, err)| err.as_ref().map(|e| (idx, e)));
+
+                for (idx, err) in iter {
+                    writeln!(f, "        {idx}: {err}")?;
+                }
+                Ok(())
+            },
+            Unregistered(unknown) => {
+                for (handle, suggestion) in unknown {
+                    if let Some(suggestion) = suggestion {
+                        writeln!(f, "`{handle}` is not a registered author. Did you mean `{suggestion}`?")?;
+                    } else {
+                        writeln!(f, "`{handle}` is not a registered author.")?;
+                    }
+                }
+                Ok(())
+            },
             MissingKey => writeln!(f, "You have to define `authors`."),
         }
     }
@@ -45,11 +141,11 @@ impl Display for AuthorError {
 impl Debug for Authors {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Authors< ")?;
-        if let Some(name) = self.0.first() {
-            write!(f, "{name}")?;
+        if let Some(author) = self.0.first() {
+            write!(f, "{}", author.name)?;
         }
-        for name in self.0.iter().skip(1) {
-            write!(f, ", {name}")?;
+        for author in self.0.iter().skip(1) {
+            write!(f, ", {}", author.name)?;
         }
         write!(f, " >")
     }
@@ -59,24 +155,30 @@ impl Debug for Authors {
 
 
 #[derive(Default, Clone, PartialEq)]
-pub struct Hints(Vec<String>);
+pub struct Tags(Vec<String>);
 #[derive(Default, Debug, Clone)]
-pub enum HintError { BadEntryType(Vec<ValueType>), BadType(ValueType), #[default] MissingKey }
-impl StrList for Hints {
-    type Error = HintError; 
+pub enum TagError { BadEntryType(Vec<ValueType>), BadType(ValueType), #[default] MissingKey }
+impl StrList for Tags {
+    type Error = TagError;
     fn from_iter<'a>(iter: impl Iterator<Item = &'a str>) -> Result<Self, Self::Error> {
-        Ok(Hints(iter.map(str::to_string).collect()))
+        let mut tags: Vec<String> = vec![];
+        for tag in iter {
+            if !tags.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        Ok(Tags(tags))
     }
 
     fn from_value_mismatch(iter: impl Iterator<Item = ValueType>) -> Self::Error {
-        HintError::BadEntryType(iter.collect())
+        TagError::BadEntryType(iter.collect())
     }
 
     fn not_sequence(type_enum: ValueType) -> Self::Error {
-        HintError::BadType(type_enum)
+        TagError::BadType(type_enum)
     }
 }
-impl Hints {
+impl Tags {
     pub fn iter(&self) -> impl Iterator<Item = &str> {
         self.0.iter().map(String::as_str)
     }
@@ -85,23 +187,21 @@ impl Hints {
     }
 }
 
-impl Display for HintError {
+impl Display for TagError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use HintError::*;
+        use TagError::*;
         match self {
-            &BadEntryType(_) => writeln!(f, "Hints must be strings."),
-            &BadType(t) => writeln!(f, "Hints should be in a list, not {t}."),
-            MissingKey => writeln!(f, "You have to define `hints`."),
+            &BadEntryType(_) => writeln!(f, "Tags must be strings."),
+            &BadType(t) => writeln!(f, "Tags should be in a list, not {t}."),
+            MissingKey => writeln!(f, "You have to define `tags`."),
         }
     }
 }
-impl Debug for Hints {
+impl Debug for Tags {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Hints ")?;
+        write!(f, "Tags ")?;
         f.debug_list()
             .entries(self.0.iter())
             .finish()
     }
 }
-
-
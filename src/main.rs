@@ -1,26 +1,79 @@
-use arcs_ctf_yaml_parser::correctness::{FlagCorrectness, CategoryCorrectness, YamlCorrectness, PointCorrectness};
+use arcs_ctf_yaml_parser::correctness::{FlagCorrectness, CategoryCorrectness, YamlCorrectness, PointCorrectness, TagCorrectness, AuthorCorrectness, KeyRequirement, RequiredKeys, MarkdownCorrectness, DifficultyCorrectness, DifficultyLevel, BaseImageCorrectness, FilePolicy};
+
+use arcs_ctf_yaml_parser::categories::parse_category_defs;
+use arcs_ctf_yaml_parser::deploy::k8s::K8sConfig;
+use arcs_ctf_yaml_parser::DEFAULT_CATEGORIES;
 
 use std::borrow::Cow;
+use std::path::PathBuf;
 
-const CATEGORIES_STR: &str = "misc,binex,foren,crypto,webex,rev";
 const COMPETITION_NAME: &str = "bcactf";
 const POINT_MULT: u64 = 25;
 
+/// Reads a size in bytes from `var`, exiting if it's set to something else.
+fn size_limit(var: &str) -> Option<u64> {
+    let size = std::env::var(var).ok()?;
+    match size.trim().parse() {
+        Ok(size) => Some(size),
+        Err(_) => {
+            eprintln!("`{var}` should be a number of bytes, not `{size}`");
+            std::process::exit(1);
+        },
+    }
+}
+
+/// One end of a difficulty's range of points. Left empty, that end is open.
+fn point_bound(bound: &str) -> Result<Option<u64>, String> {
+    let bound = bound.trim();
+    if bound.is_empty() {
+        Ok(None)
+    } else {
+        bound.parse().map(Some).map_err(|_| bound.to_string())
+    }
+}
+
 pub fn main() {
     let categories = std::env::var("CATEGORIES").ok();
+    let category_defs = std::env::var("CATEGORY_DEFS").ok();
     let comp_name = std::env::var("COMPNAME").ok();
     let point_multiple: Option<u64> = std::env::var("POINT_MULT")
         .as_ref()
         .map(String::as_str).map(str::parse)
         .map(Result::ok).ok().flatten();
+    let tags = std::env::var("TAGS").ok();
+    let difficulties = std::env::var("DIFFICULTIES").ok();
+    let authors = std::env::var("AUTHORS").ok();
+    let required_keys = std::env::var("REQUIRED_KEYS").ok();
+    let check_markdown = std::env::var("CHECK_MARKDOWN").is_ok();
+    let allowed_html = std::env::var("ALLOWED_HTML").ok();
+    let optional_keys = std::env::var("OPTIONAL_KEYS").ok();
+    let base_images = std::env::var("BASE_IMAGES").ok();
+    let max_file_size = size_limit("MAX_FILE_SIZE");
+    let max_total_size = size_limit("MAX_TOTAL_SIZE");
+    let allowed_types = std::env::var("ALLOWED_FILE_TYPES").ok();
+    let denied_types = std::env::var("DENIED_FILE_TYPES").ok();
+    let shared_roots = std::env::var("SHARED_ROOTS").ok();
+    let k8s_image = std::env::var("K8S_IMAGE").ok();
+    let k8s_namespace = std::env::var("K8S_NAMESPACE").ok();
+    let k8s_network_policy = std::env::var("K8S_NETWORK_POLICY").is_ok();
 
-    let category_correctness = if let Some(category_names) = categories {
+    let category_correctness = if let Some(defs_path) = category_defs {
+        let defs = std::fs::read_to_string(&defs_path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| parse_category_defs(&text).map_err(|err| err.to_string()));
+        match defs {
+            Ok(defs) => CategoryCorrectness::Defined(defs.into()),
+            Err(err) => {
+                eprintln!("Couldn't read the categories in `{defs_path}`: {err}");
+                std::process::exit(1);
+            },
+        }
+    } else if let Some(category_names) = categories {
         let cats = if &category_names == "DEFAULT" {
-            CATEGORIES_STR
+            DEFAULT_CATEGORIES
         } else {
             &category_names
         };
-
         let names: Vec<_> = cats.split(',').map(str::to_string).map(Cow::Owned).collect();
         CategoryCorrectness::List { names: names.into(), requires_case_match: false }
     } else {
@@ -47,12 +100,99 @@ pub fn main() {
     } else {
         PointCorrectness::None
     };
-
+    let tag_correctness = if let Some(tags) = tags {
+        let tags: Vec<_> = tags.split(',').map(str::trim).map(str::to_string).map(Cow::Owned).collect();
+        TagCorrectness::List(tags.into())
+    } else {
+        TagCorrectness::AnyTag
+    };
+    let author_correctness = if let Some(authors) = authors {
+        let authors: Vec<_> = authors.split(',').map(str::trim).map(str::to_string).map(Cow::Owned).collect();
+        AuthorCorrectness::Registry(authors.into())
+    } else {
+        AuthorCorrectness::AnyAuthor
+    };
+    let markdown_correctness = if check_markdown {
+        let allowed_html: Vec<_> = allowed_html
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .map(Cow::Owned)
+            .collect();
+        MarkdownCorrectness::Checked { allowed_html: allowed_html.into() }
+    } else {
+        MarkdownCorrectness::Unchecked
+    };
+    let mut required = RequiredKeys::default();
+    let keys = [(required_keys, KeyRequirement::Required), (optional_keys, KeyRequirement::Optional)];
+    for (keys, requirement) in keys {
+        for key in keys.iter().flat_map(|keys| keys.split(',')).map(str::trim).filter(|key| !key.is_empty()) {
+            if let Some(slot) = required.get_mut(key) {
+                *slot = requirement;
+            } else {
+                eprintln!("Unknown key `{key}`: only hints, authors, files and deploy can be made required or optional");
+                std::process::exit(1);
+            }
+        }
+    }
+    // e.g. `easy:-150,medium:100-300,hard:250-`
+    let difficulty_correctness = if let Some(levels) = difficulties {
+        let levels: Result<Vec<_>, _> = levels
+            .split(',')
+            .map(|level| {
+                let (name, range) = level.split_once(':').unwrap_or((level, "-"));
+                let (min, max) = range.split_once('-').unwrap_or((range, range));
+                Ok(DifficultyLevel::new(name.trim().to_string(), point_bound(min)?, point_bound(max)?))
+            })
+            .collect();
+        let levels = levels.unwrap_or_else(|bound: String| {
+            eprintln!("`DIFFICULTIES` should give each level a range of points like `100-300`, but `{bound}` is not a number");
+            std::process::exit(1);
+        });
+        DifficultyCorrectness::Levels(levels.into())
+    } else {
+        DifficultyCorrectness::AnyLevel
+    };
+    let image_correctness = if let Some(images) = base_images {
+        let images: Vec<_> = images.split(',').map(str::trim).map(str::to_string).map(Cow::Owned).collect();
+        BaseImageCorrectness::List(images.into())
+    } else {
+        BaseImageCorrectness::AnyImage
+    };
+    let type_list = |types: String| -> Cow<'static, [Cow<'static, str>]> {
+        types.split(',').map(str::trim).map(str::to_string).map(Cow::Owned).collect::<Vec<_>>().into()
+    };
+    let file_policy = FilePolicy {
+        max_file_size,
+        max_total_size,
+        allowed_types: allowed_types.map(type_list),
+        denied_types: denied_types.map(type_list).unwrap_or_default(),
+        shared_roots: shared_roots
+            .map(|roots| roots.split(',').map(str::trim).map(PathBuf::from).collect::<Vec<_>>().into())
+            .unwrap_or_default(),
+        fetcher: None,
+    };
 
     let yaml_correctness = YamlCorrectness::default()
         .with_flag(flag_correctness)
         .with_cats(category_correctness)
-        .with_pnts(point_correctness);
+        .with_pnts(point_correctness)
+        .with_tags(tag_correctness)
+        .with_difficulty(difficulty_correctness)
+        .with_authors(author_correctness)
+        .with_required(required)
+        .with_markdown(markdown_correctness)
+        .with_images(image_correctness)
+        .with_files(file_policy);
+
+    let default_k8s = K8sConfig::default();
+    let k8s_config = K8sConfig {
+        image_template: k8s_image.map_or(default_k8s.image_template, Cow::Owned),
+        namespace: k8s_namespace.map(Cow::Owned),
+        network_policy: k8s_network_policy,
+    };
 
-    arcs_ctf_yaml_parser::__main::main(&yaml_correctness);
+    arcs_ctf_yaml_parser::__main::main(&yaml_correctness, &k8s_config);
 }
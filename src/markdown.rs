@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, BrokenLink, CowStr, Event, LinkType, Options, Parser, Tag};
+use regex::Regex;
+
+use crate::files::structs::File;
+use crate::template::served_file;
+
+
+/// `<!-- comment -->`, `<tag attr="...">`, `</tag>`, `<br/>`, ...
+static HTML_PIECE: Lazy<Regex> = Lazy::new(
+    || Regex::new(r"(?s)<!--.*?-->|<(/?)([a-zA-Z][a-zA-Z0-9-]*)(?:\s[^<>]*)?/?>").unwrap()
+);
+static URL_SCHEME: Lazy<Regex> = Lazy::new(
+    || Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:").unwrap()
+);
+
+const UNSAFE_SCHEMES: &[&str] = &["javascript", "vbscript", "data", "file"];
+
+fn options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownIssue {
+    UndefinedReference(String),
+    EmptyLink,
+    UnsafeLink(String),
+    MissingAttachment(String),
+    DisallowedHtml(String),
+}
+impl Display for MarkdownIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use MarkdownIssue::*;
+        match self {
+            UndefinedReference(name) => write!(f, "The description links to `[{name}]`, which is never defined."),
+            EmptyLink => write!(f, "The description has a link or image without a destination."),
+            UnsafeLink(url) => write!(f, "The description links to `{url}`, which won't be rendered."),
+            MissingAttachment(name) => write!(f, "The description links to `{name}`, but no file is downloaded under that name."),
+            DisallowedHtml(tag) => write!(f, "The description uses `<{tag}>`, which isn't allowed. (use Markdown instead of raw HTML)"),
+        }
+    }
+}
+
+
+/// What a link destination points at, as far as the description is concerned.
+enum Destination<'a> {
+    Empty,
+    Unsafe,
+    Elsewhere,
+    Attachment(&'a str),
+}
+fn destination(url: &str) -> Destination<'_> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Destination::Empty;
+    }
+    if let Some(scheme) = URL_SCHEME.find(url) {
+        let scheme = scheme.as_str().trim_end_matches(':');
+        return if UNSAFE_SCHEMES.iter().any(|unsafe_scheme| unsafe_scheme.eq_ignore_ascii_case(scheme)) {
+            Destination::Unsafe
+        } else {
+            Destination::Elsewhere
+        };
+    }
+    // Fragments, site paths and placeholders are resolved somewhere else.
+    if url.starts_with(['#', '/']) || url.contains("{{") {
+        return Destination::Elsewhere;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    Destination::Attachment(path.trim_start_matches("./"))
+}
+
+/// A tag inside of some raw HTML.
+struct HtmlTag<'a> {
+    closing: bool,
+    name: &'a str,
+    source: &'a str,
+}
+
+/// Splits raw HTML into the tags it uses and everything else, in order.
+/// Comments are left out.
+fn html_pieces(raw: &str) -> Vec<Result<HtmlTag<'_>, &str>> {
+    let mut pieces = vec![];
+    let mut last = 0;
+    for piece in HTML_PIECE.captures_iter(raw) {
+        let whole = piece.get(0).unwrap();
+        if whole.start() > last {
+            pieces.push(Err(&raw[last..whole.start()]));
+        }
+        if let Some(name) = piece.get(2) {
+            pieces.push(Ok(HtmlTag {
+                closing: piece.get(1).is_some_and(|slash| !slash.is_empty()),
+                name: name.as_str(),
+                source: whole.as_str(),
+            }));
+        }
+        last = whole.end();
+    }
+    if last < raw.len() {
+        pieces.push(Err(&raw[last..]));
+    }
+    pieces
+}
+
+fn is_allowed(tag: &str, allowed_html: &[Cow<'static, str>]) -> bool {
+    allowed_html.iter().any(|allowed| allowed.eq_ignore_ascii_case(tag))
+}
+
+
+/// Checks the links, images and raw HTML of a Markdown description. Relative
+/// links have to point at a file players can download.
+pub fn check_description(text: &str, files: Option<&[File]>, allowed_html: &[Cow<'static, str>]) -> Vec<MarkdownIssue> {
+    let mut undefined = vec![];
+    let mut issues = vec![];
+
+    let callback = |link: BrokenLink| {
+        if matches!(link.link_type, LinkType::Reference | LinkType::Collapsed) {
+            undefined.push(MarkdownIssue::UndefinedReference(link.reference.to_string()));
+        }
+        None
+    };
+    for event in Parser::new_with_broken_link_callback(text, options(), Some(callback)) {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. }) => match destination(&dest_url) {
+                Destination::Empty => issues.push(MarkdownIssue::EmptyLink),
+                Destination::Unsafe => issues.push(MarkdownIssue::UnsafeLink(dest_url.to_string())),
+                Destination::Elsewhere => (),
+                Destination::Attachment(name) => if served_file(files, name).is_none() {
+                    issues.push(MarkdownIssue::MissingAttachment(name.to_string()));
+                },
+            },
+            Event::Html(raw) | Event::InlineHtml(raw) => issues.extend(
+                html_pieces(&raw)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter(|tag| !is_allowed(tag.name, allowed_html))
+                    .map(|tag| MarkdownIssue::DisallowedHtml(tag.name.to_ascii_lowercase())),
+            ),
+            _ => (),
+        }
+    }
+
+    undefined.append(&mut issues);
+    undefined.dedup();
+    undefined
+}
+
+/// Renders Markdown to HTML that's safe to put on a page. Allowed tags are
+/// kept without their attributes, any other HTML is escaped, and unsafe links
+/// are dropped.
+pub fn render_html(text: &str, allowed_html: &[Cow<'static, str>]) -> String {
+    let events = Parser::new_ext(text, options()).flat_map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => html_pieces(&raw)
+            .into_iter()
+            .map(|piece| match piece {
+                Ok(tag) if is_allowed(tag.name, allowed_html) => {
+                    let name = tag.name.to_ascii_lowercase();
+                    Event::InlineHtml(if tag.closing { format!("</{name}>") } else { format!("<{name}>") }.into())
+                },
+                Ok(HtmlTag { source, .. }) | Err(source) => Event::Text(source.to_string().into()),
+            })
+            .collect(),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) if matches!(destination(&dest_url), Destination::Unsafe) => {
+            vec![Event::Start(Tag::Link { link_type, dest_url: CowStr::Borrowed(""), title, id })]
+        },
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) if matches!(destination(&dest_url), Destination::Unsafe) => {
+            vec![Event::Start(Tag::Image { link_type, dest_url: CowStr::Borrowed(""), title, id })]
+        },
+        event => vec![event],
+    });
+
+    let mut output = String::with_capacity(text.len() * 3 / 2);
+    html::push_html(&mut output, events);
+    output
+}
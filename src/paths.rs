@@ -0,0 +1,42 @@
+use std::path::{Component, Path, PathBuf};
+
+
+/// Resolves `.` and `..` without touching the filesystem.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => if !normalized.pop() {
+                normalized.push("..");
+            },
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Where `path` really points, following symlinks for as much of it as
+/// exists, the same way the OS does when reading it. Whatever doesn't exist
+/// yet is appended with its `.` and `..` resolved.
+fn resolve(path: &Path) -> PathBuf {
+    let path = std::env::current_dir().map_or_else(|_| path.to_path_buf(), |cwd| cwd.join(path));
+    for existing in path.ancestors() {
+        if let Ok(resolved) = existing.canonicalize() {
+            let rest = path.strip_prefix(existing).unwrap_or(Path::new(""));
+            return normalize(&resolved.join(rest));
+        }
+    }
+    normalize(&path)
+}
+
+/// Whether `path` (relative to `base_path`) stays inside the challenge
+/// directory or one of the `shared_roots`, after following symlinks.
+/// Relative shared roots are relative to `base_path`.
+pub fn is_contained(base_path: &Path, path: &Path, shared_roots: &[PathBuf]) -> bool {
+    let resolved = resolve(&base_path.join(path));
+
+    std::iter::once(base_path.to_path_buf())
+        .chain(shared_roots.iter().map(|root| base_path.join(root)))
+        .any(|root| resolved.starts_with(resolve(&root)))
+}
@@ -1,15 +1,19 @@
 use serde::{Serialize, ser::SerializeStruct};
 
-use crate::{YamlShape, files::structs::File};
+use crate::{YamlShape, categories::structs::Category, files::structs::File, hints::{Hint, HintUnlock}, lists::structs::Author};
 
 impl Serialize for File {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer {
         
-        let mut base_struct = serializer.serialize_struct("FileEntry", 6)?;
+        let mut base_struct = serializer.serialize_struct("FileEntry", 9)?;
 
-        base_struct.serialize_field("path", self.path())?;
+        if let Some(path) = self.path() {
+            base_struct.serialize_field("path", path)?;
+        } else {
+            base_struct.skip_field("path")?;
+        }
         base_struct.serialize_field("visible", &self.visible())?;
 
         if let Some(alias) = self.alias() {
@@ -23,13 +27,95 @@ impl Serialize for File {
         } else {
             base_struct.skip_field("container")?;
         }
+
+        if let Some(archive) = self.archive() {
+            base_struct.serialize_field("archive", archive.to_str())?;
+        } else {
+            base_struct.skip_field("archive")?;
+        }
         
         base_struct.serialize_field("size", &self.data().map_or(0, <[_]>::len))?;
+
+        if let Some(url) = self.url() {
+            base_struct.serialize_field("url", url)?;
+        } else {
+            base_struct.skip_field("url")?;
+        }
+
+        if let Some(sha256) = self.sha256_hex() {
+            base_struct.serialize_field("sha256", &sha256)?;
+        } else {
+            base_struct.skip_field("sha256")?;
+        }
         
         base_struct.end()
     }
 }
 
+impl Serialize for Author {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer {
+
+        let mut base_struct = serializer.serialize_struct("Author", 5)?;
+
+        base_struct.serialize_field("name", &self.name)?;
+        base_struct.serialize_field("handle", self.handle())?;
+
+        if let Some(email) = &self.email {
+            base_struct.serialize_field("email", email)?;
+        } else {
+            base_struct.skip_field("email")?;
+        }
+        if let Some(discord) = &self.discord {
+            base_struct.serialize_field("discord", discord)?;
+        } else {
+            base_struct.skip_field("discord")?;
+        }
+
+        base_struct.serialize_field("role", self.role.to_str())?;
+
+        base_struct.end()
+    }
+}
+
+impl Serialize for Hint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer {
+
+        let mut base_struct = serializer.serialize_struct("Hint", 6)?;
+
+        base_struct.serialize_field("text", &self.text)?;
+        base_struct.serialize_field("cost", &self.cost)?;
+
+        match self.unlock {
+            Some(HintUnlock::AfterHint(idx)) => {
+                base_struct.serialize_field("requires", &idx)?;
+                base_struct.skip_field("unlock_after")?;
+            },
+            Some(HintUnlock::AfterTime(time)) => {
+                base_struct.skip_field("requires")?;
+                base_struct.serialize_field("unlock_after", &time.as_secs())?;
+            },
+            None => {
+                base_struct.skip_field("requires")?;
+                base_struct.skip_field("unlock_after")?;
+            },
+        }
+
+        base_struct.serialize_field("hidden", &self.hidden)?;
+
+        if let Some(release) = &self.release {
+            base_struct.serialize_field("release", release)?;
+        } else {
+            base_struct.skip_field("release")?;
+        }
+
+        base_struct.end()
+    }
+}
+
 impl Serialize for YamlShape {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -44,11 +130,14 @@ impl Serialize for YamlShape {
             base_struct.skip_field("files")?;
         }
 
+        base_struct.serialize_field("category", &self.primary_category().map(Category::as_str))?;
         base_struct.serialize_field("categories", &self.category_str_iter().collect::<Vec<_>>())?;
+        base_struct.serialize_field("tags", self.tags())?;
         base_struct.serialize_field("hints", self.hints())?;
         base_struct.serialize_field("authors", self.authors())?;
 
         base_struct.serialize_field("points", &self.points())?;
+        base_struct.serialize_field("difficulty", &self.difficulty())?;
         // base_struct.serialize_field("flag", &self.fla())?;
         // TODO: Make this work ig
 
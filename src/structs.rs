@@ -6,11 +6,16 @@ use serde_yaml::Value as YamlValue;
 
 use crate::categories::CategoryError;
 use crate::correctness::YamlCorrectness;
-use crate::deploy::error::DeployOptionsError;
+use crate::deploy::error::{AdminBotError, DeployOptionsError, DockerfileWarning};
+use crate::deploy::structs::DeployTargetType;
 use crate::files::errors::FileErrors;
 use crate::flag::FlagError;
+use crate::description::DescriptionError;
 use crate::lists::structs::AuthorError;
-use crate::lists::structs::HintError;
+use crate::hints::HintError;
+use crate::lists::structs::TagError;
+use crate::template::TemplateError;
+use crate::markdown::MarkdownIssue;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ValueType { type_enum: ValueTypeEnum }
@@ -66,16 +71,23 @@ pub enum YamlAttribVerifyError {
     Categories(CategoryError),
     Authors(AuthorError),
     Hints(HintError),
+    Tags(TagError),
     Flag(FlagError),
     Files(FileErrors),
 
     Deploy(DeployOptionsError),
+    AdminBot(AdminBotError),
 
     NameNotString(ValueType),
     PointsNotInt(ValueType),
+    DifficultyNotString(ValueType),
 
     DescNotString(ValueType),
+    Description(DescriptionError),
     VisNotBool(ValueType),
+
+    Template(TemplateError),
+    Markdown(MarkdownIssue),
 }
 
 #[derive(Debug)]
@@ -87,24 +99,37 @@ pub enum YamlVerifyError {
     OsError,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlVerifyWarning {
+    Dockerfile(DeployTargetType, DockerfileWarning),
+    NoFilesRouted(DeployTargetType),
+}
+
 impl Display for YamlAttribVerifyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use YamlAttribVerifyError::*;
         match self {
             NameNotString(vtype) => writeln!(f, "The name should be a string, not {vtype}."),
             DescNotString(vtype) => writeln!(f, "The description should be a string, not {vtype}."),
+            DifficultyNotString(vtype) => writeln!(f, "The difficulty should be a string, not {vtype}."),
             VisNotBool(vtype) => writeln!(f, "The visibility switch should be a boolean, not {vtype}."),
             
             PointsNotInt(ValueType { type_enum: ValueTypeEnum::Number }) => writeln!(f, "The value should be an positive integer, not negative or fractional."),
             PointsNotInt(vtype)  => writeln!(f, "The value should be an positive integer, not {vtype}."),
             
             Flag(flag_err) => writeln!(f, "{flag_err}"),
+            Description(desc_err) => writeln!(f, "{desc_err}"),
             Categories(cat_err) => writeln!(f, "{cat_err}"),
             Authors(author_err) => writeln!(f, "{author_err}"),
             Hints(hint_err) => writeln!(f, "{hint_err}"),
+            Tags(tag_err) => writeln!(f, "{tag_err}"),
             Files(file_errors) => writeln!(f, "{file_errors}"),
 
             Deploy(deploy_err) => writeln!(f, "{deploy_err}"),
+            AdminBot(admin_bot_err) => writeln!(f, "{admin_bot_err}"),
+
+            Template(template_err) => writeln!(f, "{template_err}"),
+            Markdown(issue) => writeln!(f, "{issue}"),
         }
     }
 }
@@ -127,3 +152,13 @@ impl Display for YamlVerifyError {
         }
     }
 }
+
+impl Display for YamlVerifyWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use YamlVerifyWarning::*;
+        match self {
+            Dockerfile(target, warning) => writeln!(f, "{target}: {warning}"),
+            NoFilesRouted(target) => writeln!(f, "{target}: There is a `{target}` deploy target, but no files have `container: {target}`."),
+        }
+    }
+}
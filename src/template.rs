@@ -0,0 +1,155 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::deploy::structs::{DeployOptions, DeployTargetType};
+use crate::files::structs::File;
+
+
+/// A `{{ key }}` placeholder inside of some text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder<'a> {
+    pub range: Range<usize>,
+    pub key: &'a str,
+}
+impl Placeholder<'_> {
+    /// The target name of a `{{ link:<target> }}` placeholder.
+    pub fn link_target(&self) -> Option<&str> {
+        self.key.strip_prefix("link:").map(str::trim)
+    }
+
+    /// What a placeholder in a description or hint stands for.
+    pub fn variable(&self) -> Result<Variable<'_>, TemplateError> {
+        if let Some(target) = self.link_target() {
+            return DeployTargetType::try_from_str(target)
+                .map(Variable::Link)
+                .ok_or_else(|| TemplateError::UnknownLinkTarget(target.to_string()));
+        }
+        if let Some(name) = self.key.strip_prefix("file:") {
+            return Ok(Variable::File(name.trim()));
+        }
+        match self.key {
+            "name" => Ok(Variable::Name),
+            "points" => Ok(Variable::Points),
+            key => DeployTargetType::try_from_str(key)
+                .map(Variable::Link)
+                .ok_or_else(|| TemplateError::UnknownVariable(key.to_string())),
+        }
+    }
+}
+
+/// `{{ name }}`, `{{ points }}`, `{{ file:<name> }}`, and `{{ link:<target> }}`
+/// (or just `{{ <target> }}`). Text that needs literal braces, like an SSTI
+/// payload, escapes them by doubling: `{{{{7*7}}}}` is shown as `{{7*7}}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variable<'a> {
+    Name,
+    Points,
+    File(&'a str),
+    Link(DeployTargetType),
+}
+
+/// A piece of templated text that gets replaced: either a placeholder, or a
+/// `{{{{` / `}}}}` escape that stands for a literal `{{` / `}}`.
+enum Piece<'a> {
+    Placeholder(Placeholder<'a>),
+    Escape(Range<usize>, &'static str),
+}
+
+fn pieces(text: &str) -> Vec<Piece<'_>> {
+    let mut found = vec![];
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|idx| idx + offset) {
+        if text[start..].starts_with("{{{{") {
+            found.push(Piece::Escape(start..start + 4, "{{"));
+            offset = start + 4;
+            // The matching `}}}}` is escaped too, so `{{{{7*7}}}}` comes out as `{{7*7}}`.
+            if let Some(close) = text[offset..].find("}}").map(|idx| idx + offset) {
+                if text[close..].starts_with("}}}}") {
+                    found.push(Piece::Escape(close..close + 4, "}}"));
+                    offset = close + 4;
+                }
+            }
+            continue;
+        }
+        let Some(end) = text[start..].find("}}").map(|idx| idx + start + 2) else { break };
+        found.push(Piece::Placeholder(Placeholder { range: start..end, key: text[start + 2..end - 2].trim() }));
+        offset = end;
+    }
+    found
+}
+
+/// Every placeholder in `text`. Escaped braces (`{{{{ ... }}}}`) aren't
+/// placeholders.
+pub fn placeholders(text: &str) -> Vec<Placeholder<'_>> {
+    pieces(text)
+        .into_iter()
+        .filter_map(|piece| match piece {
+            Piece::Placeholder(placeholder) => Some(placeholder),
+            Piece::Escape(..) => None,
+        })
+        .collect()
+}
+
+/// Replaces every placeholder that `resolve` knows about, leaving the rest
+/// untouched. Escaped braces are always unescaped.
+pub fn substitute(text: &str, mut resolve: impl FnMut(&Placeholder) -> Option<String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for piece in pieces(text) {
+        let (range, value) = match piece {
+            Piece::Placeholder(placeholder) => match resolve(&placeholder) {
+                Some(value) => (placeholder.range, value),
+                None => continue,
+            },
+            Piece::Escape(range, literal) => (range, literal.to_string()),
+        };
+        output.push_str(&text[last..range.start]);
+        output.push_str(&value);
+        last = range.end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    UnknownLinkTarget(String),
+    UndeclaredLinkTarget(DeployTargetType),
+    UnknownVariable(String),
+    UnknownFile(String),
+}
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TemplateError::*;
+        match self {
+            UnknownLinkTarget(name) => write!(f, "`{{{{ link:{name} }}}}` does not refer to a deploy target. (targets are `web`, `admin`, `nc`, and `static`)"),
+            UndeclaredLinkTarget(target) => write!(f, "`{{{{ link:{target} }}}}` is used, but there is no `{target}` target under `deploy`."),
+            UnknownVariable(key) => write!(f, "`{{{{ {key} }}}}` is not a variable. (variables are `name`, `points`, `file:<name>`, and the deploy targets; write `{{{{{{{{ ... }}}}}}}}` for literal braces)"),
+            UnknownFile(name) => write!(f, "`{{{{ file:{name} }}}}` is used, but no file is downloaded as `{name}`."),
+        }
+    }
+}
+
+/// The file players download as `name`, if there is one.
+pub fn served_file<'a>(files: Option<&'a [File]>, name: &str) -> Option<&'a File> {
+    files
+        .unwrap_or_default()
+        .iter()
+        .find(|file| file.is_served() && file.download_name() == name)
+}
+
+/// Checks that every placeholder in `text` is a variable that can be filled in.
+pub fn verify_template(text: &str, deploy: Option<&DeployOptions>, files: Option<&[File]>) -> Vec<TemplateError> {
+    placeholders(text)
+        .iter()
+        .filter_map(|placeholder| match placeholder.variable() {
+            Err(err) => Some(err),
+            Ok(Variable::Name | Variable::Points) => None,
+            Ok(Variable::File(name)) => served_file(files, name)
+                .is_none()
+                .then(|| TemplateError::UnknownFile(name.to_string())),
+            Ok(Variable::Link(target_type)) => (!deploy.is_some_and(|deploy| deploy.has_target(target_type)))
+                .then_some(TemplateError::UndeclaredLinkTarget(target_type)),
+        })
+        .collect()
+}
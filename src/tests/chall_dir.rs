@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{YamlAttribVerifyError, YamlShape, YamlVerifyError};
+
+/// A throwaway challenge directory under the system temp dir, removed on drop.
+pub struct ChallDir { path: PathBuf }
+
+impl ChallDir {
+    pub fn new(name: &str) -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "arcs-yaml-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst),
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn with_file(self, relative: &str, contents: impl AsRef<[u8]>) -> Self {
+        let path = self.path.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+        self
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+impl Drop for ChallDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+
+/// A `chall.yaml` with every required key filled in. Values are raw YAML;
+/// ones starting with an indent (like `"  - src: a.txt\n"`) go on the lines
+/// under their key.
+pub struct ChallYaml { keys: Vec<(&'static str, String)> }
+
+impl Default for ChallYaml {
+    fn default() -> Self {
+        Self {
+            keys: vec![
+                ("name", "test chall".to_string()),
+                ("description", "a test challenge".to_string()),
+                ("flag", "bcactf{flag}".to_string()),
+                ("value", "100".to_string()),
+                ("visible", "true".to_string()),
+                ("categories", "[misc]".to_string()),
+                ("authors", "[someone]".to_string()),
+                ("hints", "[]".to_string()),
+            ],
+        }
+    }
+}
+
+impl ChallYaml {
+    /// Replaces `key`, or adds it if it isn't there yet.
+    pub fn with(mut self, key: &'static str, yaml: impl Into<String>) -> Self {
+        let yaml = yaml.into();
+        match self.keys.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, value)) => *value = yaml,
+            None => self.keys.push((key, yaml)),
+        }
+        self
+    }
+
+    pub fn with_files(self, yaml: impl Into<String>) -> Self {
+        self.with("files", yaml)
+    }
+}
+
+impl Display for ChallYaml {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (key, value) in &self.keys {
+            if value.starts_with(' ') {
+                writeln!(f, "{key}:\n{}", value.trim_end_matches('\n'))?;
+            } else {
+                writeln!(f, "{key}: {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The errors from parts of the YAML, panicking if it parsed or failed some
+/// other way.
+pub fn part_errors(parsed: Result<YamlShape, YamlVerifyError>) -> Vec<YamlAttribVerifyError> {
+    match parsed {
+        Err(YamlVerifyError::PartErrors(errors)) => errors,
+        parsed => panic!("Expected errors in parts of the YAML, but got {parsed:?}"),
+    }
+}
@@ -0,0 +1,480 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::correctness::{BaseImageCorrectness, YamlCorrectness};
+use crate::deploy::chall_slug;
+use crate::deploy::dockerfile::{parse_dockerfile, ExposedPorts};
+use crate::deploy::error::{AdminBotError, DeployOptionsError, DockerfileError, DockerfileWarning};
+use crate::files::errors::FileErrors;
+use crate::files::structs::{ContainerType, FileSource};
+use crate::deploy::k8s::{K8sConfig, manifests_to_yaml};
+use crate::deploy::links::HostConfig;
+use crate::deploy::structs::{DeployTargetType, NetworkProtocol};
+use crate::template::TemplateError;
+use crate::{YamlAttribVerifyError, YamlShape, YamlVerifyWarning};
+
+use super::chall_dir::{part_errors, ChallDir};
+
+const CHALL_YAML: &str = r#"
+name: deploy test
+description: a challenge with a deployment
+flag:
+  file: flag.txt
+value: 100
+visible: true
+categories: [webex]
+authors: [someone]
+hints: []
+deploy:
+  web:
+    expose: 8080/tcp
+"#;
+
+#[test]
+fn dockerfile_parsing() {
+    let dockerfile = parse_dockerfile(r#"
+# build stage
+FROM --platform=linux/amd64 rust:1.70 AS builder
+COPY --chown=1000 src /build/src
+RUN cargo build \
+    --release
+
+FROM python:3.11-slim
+COPY --from=builder /build/target/release/chall /app/chall
+COPY ["flag.txt", "server.py", "/app/"]
+EXPOSE 8080/tcp 9000-9001/udp $EXTRA
+"#);
+
+    assert_eq!(dockerfile.base_images, ["rust:1.70", "python:3.11-slim"]);
+    assert_eq!(
+        dockerfile.exposed,
+        [ExposedPorts { start: 8080, end: 8080, is_tcp: true }, ExposedPorts { start: 9000, end: 9001, is_tcp: false }],
+    );
+    assert!(dockerfile.exposed[1].contains(&NetworkProtocol::Udp(9001)));
+    assert!(!dockerfile.exposed[1].contains(&NetworkProtocol::Tcp(9001)));
+
+    let bad_ranges = parse_dockerfile("FROM scratch\nEXPOSE 0-4294967295 2000-1000 70000\n");
+    assert!(bad_ranges.exposed.is_empty());
+    assert_eq!(bad_ranges.unresolved_exposes, ["0-4294967295", "2000-1000", "70000"]);
+    assert_eq!(dockerfile.unresolved_exposes, ["$EXTRA"]);
+    assert_eq!(dockerfile.copies.len(), 2);
+    assert_eq!(dockerfile.copies[0].sources, ["src"]);
+    assert_eq!(dockerfile.copies[1].sources, ["flag.txt", "server.py"]);
+}
+
+#[test]
+fn base_image_allow_list() {
+    let images = BaseImageCorrectness::List(Cow::Borrowed(&[Cow::Borrowed("python"), Cow::Borrowed("node:20")]));
+    assert!(images.check("python:3.11-slim"));
+    assert!(images.check("python"));
+    assert!(images.check("node:20"));
+    assert!(!images.check("node:18"));
+    assert!(!images.check("ubuntu:22.04"));
+    assert!(images.check("scratch"));
+}
+
+#[test]
+fn dockerfile_checks() {
+    let chall = ChallDir::new("dockerfile-checks")
+        .with_file("flag.txt", "bcactf{flag}")
+        .with_file("Dockerfile", "FROM ubuntu:22.04\nCOPY . /app\nEXPOSE 80\n");
+
+    let correctness = YamlCorrectness::default()
+        .with_images(BaseImageCorrectness::List(Cow::Borrowed(&[Cow::Borrowed("python")])));
+    let errors = part_errors(YamlShape::try_from_str(CHALL_YAML, &correctness, Some(chall.path())));
+    let [YamlAttribVerifyError::Deploy(DeployOptionsError::Dockerfiles(targets))] = errors.as_slice() else {
+        panic!("Expected only Dockerfile errors, but got {errors:?}");
+    };
+    assert_eq!(targets.len(), 1);
+    assert_eq!(targets[0].0, DeployTargetType::Web);
+    assert_eq!(
+        targets[0].1,
+        [
+            DockerfileError::ExposeMismatch { declared: NetworkProtocol::Tcp(8080), found: vec![ExposedPorts { start: 80, end: 80, is_tcp: true }] },
+            DockerfileError::BaseImageNotAllowed("ubuntu:22.04".to_string()),
+            DockerfileError::CopiesFlag { line: 2, source: ".".to_string() },
+        ],
+    );
+}
+
+#[test]
+fn dockerfile_missing_is_warning() {
+    let chall = ChallDir::new("dockerfile-missing").with_file("flag.txt", "bcactf{flag}");
+    let parsed = YamlShape::try_from_str(CHALL_YAML, &YamlCorrectness::default(), Some(chall.path()));
+
+    let shape = parsed.expect("A missing Dockerfile should only warn");
+    assert!(
+        shape.warnings().iter().any(|warning| matches!(
+            warning,
+            YamlVerifyWarning::Dockerfile(DeployTargetType::Web, DockerfileWarning::Missing(_)),
+        )),
+        "Expected a missing Dockerfile warning, but got {:?}",
+        shape.warnings(),
+    );
+}
+
+#[test]
+fn dockerfile_key() {
+    let chall = ChallDir::new("dockerfile-key")
+        .with_file("flag.txt", "bcactf{flag}")
+        .with_file("Dockerfile", "FROM python\nEXPOSE 8080\n")
+        .with_file("docker/web.Dockerfile", "FROM python\nEXPOSE 80\n");
+    let yaml = format!("{CHALL_YAML}    dockerfile: docker/web.Dockerfile\n");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    let [YamlAttribVerifyError::Deploy(DeployOptionsError::Dockerfiles(targets))] = errors.as_slice() else {
+        panic!("Expected only Dockerfile errors, but got {errors:?}");
+    };
+    assert_eq!(
+        targets[0].1,
+        [DockerfileError::ExposeMismatch { declared: NetworkProtocol::Tcp(8080), found: vec![ExposedPorts { start: 80, end: 80, is_tcp: true }] }],
+    );
+
+    let yaml = format!("{CHALL_YAML}    dockerfile: ../Dockerfile\n");
+    let parsed = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path()));
+    assert!(
+        format!("{parsed:?}").contains("EscapesRoot"),
+        "Expected a Dockerfile outside of the challenge to be rejected, but got {parsed:?}",
+    );
+}
+
+const COMPOSE_YAML: &str = r#"
+name: Compose Test!
+description: a challenge with a deployment
+flag: bcactf{flag}
+value: 100
+visible: true
+categories: [webex]
+authors: [someone]
+hints: []
+deploy:
+  web:
+    expose: 8080/tcp
+    src: web
+  nc:
+    expose: 1337/udp
+    replicas: 3
+    dockerfile: docker/nc.Dockerfile
+"#;
+
+#[test]
+fn compose_generation() {
+    let chall = ChallDir::new("compose");
+    let shape = YamlShape::try_from_str(COMPOSE_YAML, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    assert_eq!(
+        shape.compose().unwrap().to_yaml(),
+        r#"services:
+  nc:
+    build:
+      context: .
+      dockerfile: docker/nc.Dockerfile
+    ports:
+    - 1337/udp
+    deploy:
+      replicas: 3
+    networks:
+    - compose-test
+  web:
+    build:
+      context: ./web
+    ports:
+    - 8080:8080/tcp
+    deploy:
+      replicas: 1
+    networks:
+    - compose-test
+networks:
+  compose-test: {}
+"#,
+    );
+}
+
+#[test]
+fn slugs() {
+    assert_eq!(chall_slug("Compose Test!"), "compose-test");
+    assert_eq!(chall_slug("  --web__101--  "), "web-101");
+
+    let slug = chall_slug("挑战");
+    assert!(slug.starts_with("chall-") && slug.len() == "chall-".len() + 8, "Unexpected fallback slug {slug}");
+    assert_eq!(slug, chall_slug("挑战"));
+    assert_ne!(slug, chall_slug("🚩"));
+}
+
+#[test]
+fn k8s_generation() {
+    let chall = ChallDir::new("k8s");
+    let shape = YamlShape::try_from_str(COMPOSE_YAML, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let config = K8sConfig {
+        image_template: Cow::Borrowed("registry.example.com/{chall}/{target}:v1"),
+        namespace: Some(Cow::Borrowed("challs")),
+        network_policy: true,
+    };
+
+    let manifests = shape.k8s_manifests(&config).unwrap();
+    let kinds: Vec<_> = manifests.iter().map(|manifest| manifest.kind).collect();
+    assert_eq!(kinds, ["Deployment", "Service", "NetworkPolicy", "Deployment", "Service", "NetworkPolicy"]);
+
+    let nc_manifests = manifests_to_yaml(&manifests[3..]);
+    assert_eq!(nc_manifests, manifests_to_yaml(&shape.k8s_manifests(&config).unwrap()[3..]));
+    assert_eq!(
+        nc_manifests,
+        r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: compose-test-nc
+  namespace: challs
+  labels:
+    app: compose-test
+    target: nc
+spec:
+  replicas: 3
+  selector:
+    matchLabels:
+      app: compose-test
+      target: nc
+  template:
+    metadata:
+      labels:
+        app: compose-test
+        target: nc
+    spec:
+      containers:
+      - name: nc
+        image: registry.example.com/compose-test/nc:v1
+        ports:
+        - containerPort: 1337
+          protocol: UDP
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: compose-test-nc
+  namespace: challs
+  labels:
+    app: compose-test
+    target: nc
+spec:
+  selector:
+    app: compose-test
+    target: nc
+  ports:
+  - name: nc
+    port: 1337
+    targetPort: 1337
+    protocol: UDP
+---
+apiVersion: networking.k8s.io/v1
+kind: NetworkPolicy
+metadata:
+  name: compose-test-nc
+  namespace: challs
+  labels:
+    app: compose-test
+    target: nc
+spec:
+  podSelector:
+    matchLabels:
+      app: compose-test
+      target: nc
+  policyTypes:
+  - Ingress
+  ingress:
+  - ports:
+    - port: 1337
+      protocol: UDP
+"#,
+    );
+}
+
+#[test]
+fn description_links() {
+    let chall = ChallDir::new("links");
+    let yaml = COMPOSE_YAML.replace(
+        "description: a challenge with a deployment",
+        "description: 'Visit {{ link:web }} or run `{{nc}}` for {{ points }} points.'",
+    );
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let config = HostConfig::new("challs.bcactf.com");
+    assert_eq!(
+        shape.render_description(&config),
+        "Visit https://compose-test.challs.bcactf.com/ or run `nc challs.bcactf.com 1337` for 100 points.",
+    );
+
+    let yaml = COMPOSE_YAML.replace(
+        "description: a challenge with a deployment",
+        "description: '{{ link:admin }} {{ link:ftp }} {{ other }} {{ file:chall.zip }}'",
+    );
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    let template_errors: Vec<_> = errors
+        .into_iter()
+        .map(|err| if let YamlAttribVerifyError::Template(err) = err { err } else { panic!("Expected a template error, not {err:?}") })
+        .collect();
+    assert_eq!(
+        template_errors,
+        [
+            TemplateError::UndeclaredLinkTarget(DeployTargetType::Admin),
+            TemplateError::UnknownLinkTarget("ftp".to_string()),
+            TemplateError::UnknownVariable("other".to_string()),
+            TemplateError::UnknownFile("chall.zip".to_string()),
+        ],
+    );
+}
+
+const ADMIN_BOT_YAML: &str = r#"
+name: admin bot test
+description: an xss challenge
+flag: bcactf{cookie-monster}
+value: 100
+visible: true
+categories: [webex]
+authors: [someone]
+hints: []
+deploy:
+  web:
+    expose: 8080/tcp
+  admin:
+    expose: 3000/tcp
+admin_bot:
+  visits: web
+  cookie:
+    name: flag
+    value: "{{ flag }}"
+  timeout: 5
+  allowed_urls: ["^https://admin-bot-test\\."]
+"#;
+
+#[test]
+fn admin_bot() {
+    let chall = ChallDir::new("admin-bot");
+    let shape = YamlShape::try_from_str(ADMIN_BOT_YAML, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let admin_bot = shape.admin_bot().unwrap();
+    assert_eq!(admin_bot.visits, DeployTargetType::Web);
+    assert_eq!(admin_bot.timeout, std::time::Duration::from_secs(5));
+    assert_eq!(admin_bot.cookie.as_ref().unwrap().value, "bcactf{cookie-monster}");
+    assert!(admin_bot.allows_url("https://admin-bot-test.challs.bcactf.com/"));
+    assert!(!admin_bot.allows_url("https://evil.com/"));
+
+    let yaml = ADMIN_BOT_YAML.replace("  web:\n    expose: 8080/tcp\n", "");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(errors.as_slice(), [YamlAttribVerifyError::AdminBot(AdminBotError::UndeclaredTarget(DeployTargetType::Web))]), "Expected an undeclared target error, but got {errors:?}");
+}
+
+const STATIC_YAML: &str = r#"
+name: static test
+description: 'The site is at {{ link:static }}'
+flag: bcactf{static}
+value: 100
+visible: true
+categories: [webex]
+authors: [someone]
+hints: []
+files:
+  - src: extra.js
+    container: static
+  - src: handout.txt
+deploy:
+  static:
+    dir: public
+    cache: max-age=3600
+"#;
+
+#[test]
+fn static_site() {
+    let chall = ChallDir::new("static")
+        .with_file("public/index.html", "<h1>hi</h1>")
+        .with_file("extra.js", "console.log(1)")
+        .with_file("handout.txt", "hello");
+    let shape = YamlShape::try_from_str(STATIC_YAML, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let site = shape.static_site().unwrap();
+    assert_eq!(site.target.dir, std::path::Path::new("public"));
+    assert_eq!(site.target.index, "index.html");
+    assert_eq!(site.target.cache.as_deref(), Some("max-age=3600"));
+    assert_eq!(site.files.len(), 1);
+    assert_eq!(site.files[0].data(), Some(b"console.log(1)".as_slice()));
+
+    assert_eq!(
+        shape.render_description(&HostConfig::new("challs.bcactf.com")),
+        "The site is at https://challs.bcactf.com/static/static-test/",
+    );
+
+    let yaml = STATIC_YAML.replace("    dir: public\n", "    dir: public\n    index: home.html\n");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(errors.as_slice(), [YamlAttribVerifyError::Deploy(DeployOptionsError::Parts { static_site, .. })] if static_site.is_some()), "Expected a static target error, but got {errors:?}");
+}
+
+#[test]
+fn file_containers() {
+    let chall = ChallDir::new("file-containers")
+        .with_file("public/index.html", "<h1>hi</h1>")
+        .with_file("extra.js", "console.log(1)")
+        .with_file("more.js", "console.log(2)")
+        .with_file("handout.txt", "hello");
+
+    let yaml = STATIC_YAML.replace("src: extra.js\n    container: static", "src: '*.js'\n    container: nc");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    let [YamlAttribVerifyError::Files(FileErrors::UndeclaredContainers(files))] = errors.as_slice() else {
+        panic!("Expected only file errors, but got {errors:?}");
+    };
+    assert_eq!(
+        files.as_slice(),
+        [
+            (FileSource::Local(PathBuf::from("extra.js")), ContainerType::Nc),
+            (FileSource::Local(PathBuf::from("more.js")), ContainerType::Nc),
+        ],
+    );
+
+    let yaml = STATIC_YAML.replace("    container: static\n", "");
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    assert_eq!(shape.warnings(), [YamlVerifyWarning::NoFilesRouted(DeployTargetType::Static)]);
+}
+
+#[test]
+fn hint_and_file_variables() {
+    let chall = ChallDir::new("variables").with_file("dist/chall.zip", "zip");
+    let yaml = r#"
+name: Variable Test
+description: "{{ name }} is worth {{ points }}. Get [the handout]({{ file:chall.zip }})."
+flag: bcactf{flag}
+value: 150
+visible: true
+categories: [misc]
+authors: [someone]
+hints:
+  - "Download {{ file:chall.zip }} first."
+  - "There's no {{ nc }} for this one."
+files:
+  - src: dist/chall.zip
+"#;
+    let errors = part_errors(YamlShape::try_from_str(yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Template(TemplateError::UndeclaredLinkTarget(DeployTargetType::Nc))],
+    ));
+
+    let yaml = yaml.replace("There's no {{ nc }} for this one.", "It's worth {{ points }}.");
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let config = HostConfig::new("challs.bcactf.com");
+    assert_eq!(
+        shape.render_description(&config),
+        "Variable Test is worth 150. Get [the handout](https://challs.bcactf.com/files/variable-test/chall.zip).",
+    );
+    assert_eq!(
+        shape.render_hints(&config),
+        ["Download https://challs.bcactf.com/files/variable-test/chall.zip first.", "It's worth 150."],
+    );
+
+    // SSTI payloads escape their braces so they aren't taken as variables
+    let yaml = yaml.replace("It's worth {{ points }}.", "Try {{{{7*7}}}} on {{ name }}.");
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    assert_eq!(shape.render_hints(&config)[1], "Try {{7*7}} on Variable Test.");
+
+    let yaml = yaml.replace("Try {{{{7*7}}}}", "Try {{7*7}}");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Template(TemplateError::UnknownVariable(key))] if key == "7*7",
+    ));
+}
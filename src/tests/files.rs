@@ -0,0 +1,346 @@
+use crate::correctness::{FilePolicy, YamlCorrectness};
+use crate::files::errors::{ArchiveErr, ChecksumErr, DataReadErr, DownloadNameErr, FileErrors, FileParseErr, FilePathErr, NameIssue};
+use crate::files::fetch::{Fetcher, SharedFetcher};
+use crate::{YamlAttribVerifyError, YamlShape, YamlVerifyError};
+
+use super::chall_dir::{part_errors, ChallDir, ChallYaml};
+
+fn file_errors(parsed: Result<YamlShape, YamlVerifyError>) -> Vec<Option<FileParseErr>> {
+    let errors = part_errors(parsed);
+    let [YamlAttribVerifyError::Files(FileErrors::EntryErrors(entries))] = errors.as_slice() else {
+        panic!("Expected only file errors, but got {errors:?}");
+    };
+    entries.clone()
+}
+
+#[test]
+fn glob_and_directory_entries() {
+    let chall = ChallDir::new("globs")
+        .with_file("dist/b.so", "b")
+        .with_file("dist/a.so", "a")
+        .with_file("dist/readme.txt", "readme")
+        .with_file("handouts/z.txt", "z")
+        .with_file("handouts/nested/y.txt", "y");
+
+    let yaml = ChallYaml::default().with_files("  - src: dist/*.so\n  - src: handouts\n").to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let paths: Vec<_> = shape.file_path_iter().unwrap().map(|path| path.to_str().unwrap()).collect();
+    assert_eq!(paths, ["dist/a.so", "dist/b.so", "handouts/nested/y.txt", "handouts/z.txt"]);
+    assert_eq!(shape.files().unwrap()[0].data(), Some(b"a".as_slice()));
+}
+
+#[test]
+fn hidden_files_and_links() {
+    let chall = ChallDir::new("hidden")
+        .with_file("handouts/a.txt", "a")
+        .with_file("handouts/.DS_Store", "junk")
+        .with_file("handouts/.git/HEAD", "ref: refs/heads/main");
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(chall.path().join("handouts"), chall.path().join("handouts/loop")).unwrap();
+
+    let yaml = ChallYaml::default().with_files("  - src: handouts\n  - src: handouts\n    archive: zip\n").to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let paths: Vec<_> = shape.file_path_iter().unwrap().map(|path| path.to_str().unwrap()).collect();
+    assert_eq!(paths, ["handouts/a.txt", "handouts"]);
+
+    let zip = &shape.files().unwrap()[1];
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip.data().unwrap())).unwrap();
+    let names: Vec<_> = (0..archive.len()).map(|idx| archive.by_index(idx).unwrap().name().unwrap().to_string()).collect();
+    assert_eq!(names, ["a.txt"]);
+
+    let yaml = ChallYaml::default().with_files("  - src: handouts/*\n    include_hidden: true\n").to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let paths: Vec<_> = shape.file_path_iter().unwrap().map(|path| path.to_str().unwrap()).collect();
+    assert_eq!(paths, ["handouts/.DS_Store", "handouts/.git/HEAD", "handouts/a.txt"]);
+}
+
+#[test]
+fn glob_errors() {
+    let chall = ChallDir::new("glob-errors").with_file("dist/a.so", "a").with_file("dist/b.so", "b");
+
+    let yaml = ChallYaml::default().with_files("  - src: dist/*.dll\n  - src: dist/*.so\n    as: lib.so\n").to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+
+    assert!(matches!(
+        entries.as_slice(),
+        [
+            Some(FileParseErr::Parts { path: Some(FilePathErr::NoMatches(_)), .. }),
+            Some(FileParseErr::Parts { path: Some(FilePathErr::AliasForMany(_, 2)), .. }),
+        ],
+    ), "Unexpected errors {entries:?}");
+}
+
+#[test]
+fn archived_directories() {
+    let chall = ChallDir::new("archives")
+        .with_file("handout/b.txt", "b")
+        .with_file("handout/a/c.txt", "c");
+
+    let yaml = ChallYaml::default().with_files("  - src: handout\n    archive: zip\n  - src: handout\n    archive: tar.gz\n    as: dist.tgz\n").to_string();
+    let first = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(chall.path().join("handout/b.txt"), "b").unwrap();
+    let second = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let [zip, tar_gz] = first.files().unwrap() else { panic!("Expected exactly two archives") };
+    assert_eq!(zip.alias(), Some("handout.zip"));
+    assert_eq!(tar_gz.alias(), Some("dist.tgz"));
+    assert!(zip.data().unwrap().starts_with(b"PK"));
+    assert!(tar_gz.data().unwrap().starts_with(&[0x1f, 0x8b]));
+    assert_eq!(first.files(), second.files(), "Archives should be reproducible");
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip.data().unwrap())).unwrap();
+    let names: Vec<_> = (0..archive.len()).map(|idx| archive.by_index(idx).unwrap().name().unwrap().to_string()).collect();
+    assert_eq!(names, ["a/c.txt", "b.txt"]);
+
+    let yaml = ChallYaml::default().with_files("  - src: handout/b.txt\n    archive: zip\n  - src: handout\n    archive: rar\n").to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(
+        entries.as_slice(),
+        [
+            Some(FileParseErr::Parts { archive: Some(ArchiveErr::NotDirectory(_)), .. }),
+            Some(FileParseErr::Parts { archive: Some(ArchiveErr::BadFormat(_)), .. }),
+        ],
+    ), "Unexpected errors {entries:?}");
+}
+
+#[test]
+fn content_hashes() {
+    // sha256("hello")
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    let chall = ChallDir::new("hashes").with_file("hello.txt", "hello");
+
+    let yaml = ChallYaml::default().with_files(format!("  - src: hello.txt\n    sha256: {}\n", HELLO_SHA256.to_uppercase())).to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let file = &shape.files().unwrap()[0];
+    assert_eq!(file.sha256_hex().as_deref(), Some(HELLO_SHA256));
+
+    let serialized = serde_yaml::to_value(file).unwrap();
+    assert_eq!(serialized["sha256"].as_str(), Some(HELLO_SHA256));
+
+    let yaml = ChallYaml::default().with_files(format!(
+        "  - src: hello.txt\n    sha256: {}\n  - src: hello.txt\n    sha256: abc\n  - src: hello.txt\n    sha256: '{}'\n",
+        "0".repeat(64),
+        "+0".repeat(32),
+    )).to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    let checksum_errs: Vec<_> = entries
+        .iter()
+        .map(|entry| match entry {
+            Some(FileParseErr::Parts { sha256, .. }) => sha256.as_deref(),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        checksum_errs.as_slice(),
+        [Some(ChecksumErr::Mismatch { .. }), Some(ChecksumErr::BadFormat(_)), Some(ChecksumErr::BadFormat(_))],
+    ), "Unexpected errors {entries:?}");
+
+    let chall = chall.with_file("world.txt", "world");
+    let yaml = ChallYaml::default().with_files(format!("  - src: '*.txt'\n    sha256: {HELLO_SHA256}\n")).to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(
+        entries.as_slice(),
+        [Some(FileParseErr::Parts { path: Some(FilePathErr::ChecksumForMany(_, 2)), .. })],
+    ), "Unexpected errors {entries:?}");
+}
+
+#[test]
+fn file_policy() {
+    let chall = ChallDir::new("file-policy")
+        .with_file("big.txt", "0123456789")
+        .with_file("chall", "\x7fELF")
+        .with_file("notes.txt", "notes");
+
+    let yaml = ChallYaml::default().with_files("  - src: big.txt\n  - src: chall\n  - src: notes.txt\n").to_string();
+    let correctness = YamlCorrectness::default().with_files(FilePolicy {
+        max_file_size: Some(8),
+        denied_types: vec!["elf".into()].into(),
+        ..FilePolicy::default()
+    });
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())));
+
+    assert!(matches!(
+        entries.as_slice(),
+        [
+            Some(FileParseErr::Parts { data: Some(too_large), .. }),
+            Some(FileParseErr::Parts { data: Some(disallowed), .. }),
+            None,
+        ] if matches!(**too_large, DataReadErr::TooLarge { size: 10, limit: 8, .. })
+            && matches!(&**disallowed, DataReadErr::DisallowedType { file_type, .. } if file_type == "elf")
+    ));
+
+    // Files copied into a container aren't kept, but they're still checked
+    let yaml = ChallYaml::default().with_files("  - src: big.txt\n    container: nc\n  - src: chall\n    container: nc\n").to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())));
+    assert!(matches!(
+        entries.as_slice(),
+        [
+            Some(FileParseErr::Parts { data: Some(too_large), .. }),
+            Some(FileParseErr::Parts { data: Some(disallowed), .. }),
+        ] if matches!(**too_large, DataReadErr::TooLarge { .. })
+            && matches!(**disallowed, DataReadErr::DisallowedType { .. })
+    ), "Unexpected errors {entries:?}");
+
+    let yaml = ChallYaml::default().with_files("  - src: big.txt\n  - src: notes.txt\n").to_string();
+    let correctness = YamlCorrectness::default().with_files(FilePolicy {
+        max_total_size: Some(12),
+        allowed_types: Some(vec!["txt".into()].into()),
+        ..FilePolicy::default()
+    });
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Files(FileErrors::TotalTooLarge { size: 15, limit: 12 })]
+    ));
+
+    // A binary doesn't get through an allow-list by being renamed
+    let chall = chall.with_file("renamed.txt", "\x7fELF");
+    let yaml = ChallYaml::default().with_files("  - src: notes.txt\n  - src: renamed.txt\n").to_string();
+    let correctness = YamlCorrectness::default().with_files(FilePolicy {
+        allowed_types: Some(vec!["txt".into()].into()),
+        ..FilePolicy::default()
+    });
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())));
+    assert!(matches!(
+        entries.as_slice(),
+        [None, Some(FileParseErr::Parts { data: Some(disallowed), .. })]
+            if matches!(&**disallowed, DataReadErr::DisallowedType { file_type, .. } if file_type == "elf")
+    ), "Unexpected errors {entries:?}");
+}
+
+#[test]
+fn exposed_flag_file() {
+    let chall = ChallDir::new("exposed-flag")
+        .with_file("flag.txt", "bcactf{exposed}")
+        .with_file("dist/flag.txt", "bcactf{exposed}")
+        .with_file("dist/chall.py", "print()");
+
+    let parse = |yaml: ChallYaml| YamlShape::try_from_str(
+        &yaml.with("flag", "{ file: dist/flag.txt }").to_string(),
+        &YamlCorrectness::default(),
+        Some(chall.path()),
+    );
+    let exposed = |files: &str| match parse(ChallYaml::default().with_files(files)) {
+        Ok(_) => None,
+        parsed => match part_errors(parsed).as_slice() {
+            [YamlAttribVerifyError::Files(FileErrors::FlagExposed(path))] => Some(path.clone()),
+            errors => panic!("Expected only an exposed flag, but got {errors:?}"),
+        },
+    };
+
+    assert_eq!(exposed("  - src: dist/*\n").unwrap(), std::path::Path::new("dist/flag.txt"));
+    assert_eq!(exposed("  - src: dist\n    archive: zip\n").unwrap(), std::path::Path::new("dist"));
+    assert_eq!(exposed("  - src: flag.txt\n"), None);
+    assert_eq!(exposed("  - src: dist/flag.txt\n    visible: false\n  - src: dist/chall.py\n"), None);
+
+    let static_site = ChallYaml::default()
+        .with_files("  - src: dist/chall.py\n    visible: false\n")
+        .with("deploy", "  static:\n    dir: dist\n    index: chall.py\n");
+    let errors = part_errors(parse(static_site));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Files(FileErrors::FlagServed(dir))] if dir == std::path::Path::new("dist"),
+    ), "Expected the static site to serve the flag, but got {errors:?}");
+}
+
+#[test]
+fn download_names() {
+    let chall = ChallDir::new("download-names")
+        .with_file("a/chall", "a")
+        .with_file("b/Chall", "b")
+        .with_file("solve.py", "solve")
+        .with_file("notes.txt", "notes");
+
+    let yaml = ChallYaml::default().with_files(concat!(
+        "  - src: a/chall\n",
+        "  - src: b/Chall\n",
+        "  - src: solve.py\n    as: \"../solve.py\"\n",
+        "  - src: notes.txt\n    as: \"CON.txt\"\n",
+    )).to_string();
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+    let [YamlAttribVerifyError::Files(FileErrors::DownloadNames(names))] = errors.as_slice() else {
+        panic!("Expected only download name errors, but got {errors:?}");
+    };
+
+    assert!(matches!(
+        names.as_slice(),
+        [
+            DownloadNameErr::Collision { name, .. },
+            DownloadNameErr::Unportable { issue: NameIssue::Separator('/'), .. },
+            DownloadNameErr::Unportable { issue: NameIssue::ReservedName, .. },
+        ] if name == "Chall"
+    ));
+
+    let yaml = ChallYaml::default().with_files("  - src: a/chall\n  - src: b/Chall\n    visible: false\n").to_string();
+    assert!(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).is_ok());
+}
+
+#[derive(Debug)]
+struct StaticFetcher(&'static [u8]);
+impl Fetcher for StaticFetcher {
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+        if url.ends_with("missing.bin") {
+            Err("404 Not Found".to_string())
+        } else {
+            Ok(self.0.to_vec())
+        }
+    }
+}
+
+#[test]
+fn remote_files() {
+    // sha256("hello")
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    const URL: &str = "https://storage.example.com/chall/disk.img";
+    let chall = ChallDir::new("remote");
+
+    let yaml = ChallYaml::default().with_files(format!("  - url: {URL}\n    sha256: {HELLO_SHA256}\n")).to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    let file = &shape.files().unwrap()[0];
+    assert_eq!((file.url(), file.data()), (Some(URL), None));
+    assert_eq!(file.download_name(), "disk.img");
+
+    assert_eq!(file.path(), None);
+    assert_eq!(shape.file_path_iter().unwrap().count(), 0);
+
+    let serialized = serde_yaml::to_value(file).unwrap();
+    assert_eq!(serialized["url"].as_str(), Some(URL));
+    assert_eq!(serialized["sha256"].as_str(), Some(HELLO_SHA256));
+    assert!(serialized.get("path").is_none());
+
+    // The query and fragment aren't part of the name it's downloaded under
+    let yaml = ChallYaml::default().with_files(format!("  - url: {URL}?token=a/b#top\n    sha256: {HELLO_SHA256}\n")).to_string();
+    let shape = YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    assert_eq!(shape.files().unwrap()[0].download_name(), "disk.img");
+
+    let fetching = YamlCorrectness::default().with_files(FilePolicy {
+        fetcher: Some(SharedFetcher(std::sync::Arc::new(StaticFetcher(b"hello")))),
+        ..FilePolicy::default()
+    });
+    let shape = YamlShape::try_from_str(&yaml, &fetching, Some(chall.path())).unwrap();
+    assert_eq!(shape.files().unwrap()[0].data(), Some(b"hello".as_slice()));
+
+    let yaml = ChallYaml::default().with_files(format!(concat!(
+        "  - url: {url}\n",
+        "  - url: ftp://storage.example.com/disk.img\n    sha256: {sha}\n",
+        "  - url: {url}\n    src: disk.img\n    sha256: {sha}\n",
+        "  - url: https://storage.example.com/missing.bin\n    sha256: {sha}\n",
+        "  - url: {url}\n    sha256: {zeros}\n",
+    ), url = URL, sha = HELLO_SHA256, zeros = "0".repeat(64))).to_string();
+    let entries = file_errors(YamlShape::try_from_str(&yaml, &fetching, Some(chall.path())));
+    assert!(matches!(
+        entries.as_slice(),
+        [
+            Some(FileParseErr::Parts { sha256: Some(missing), .. }),
+            Some(FileParseErr::Parts { path: Some(FilePathErr::BadUrl(_)), .. }),
+            Some(FileParseErr::Parts { path: Some(FilePathErr::SrcAndUrl), .. }),
+            Some(FileParseErr::Parts { data: Some(fetch_failed), .. }),
+            Some(FileParseErr::Parts { sha256: Some(mismatch), .. }),
+        ] if matches!(**missing, ChecksumErr::MissingForUrl)
+            && matches!(**fetch_failed, DataReadErr::FetchFailed { .. })
+            && matches!(**mismatch, ChecksumErr::Mismatch { .. })
+    ), "Unexpected errors {entries:?}");
+}
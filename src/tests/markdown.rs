@@ -0,0 +1,78 @@
+use crate::correctness::{MarkdownCorrectness, YamlCorrectness};
+use crate::deploy::links::HostConfig;
+use crate::deploy::structs::DeployTargetType;
+use crate::markdown::MarkdownIssue;
+use crate::template::TemplateError;
+use crate::{YamlAttribVerifyError, YamlShape, YamlVerifyError};
+
+use super::chall_dir::{part_errors, ChallDir, ChallYaml};
+
+/// The shared fixture with `description` and a served `chall.zip` and hidden `solve.py`.
+fn described(description: &str) -> ChallYaml {
+    ChallYaml::default()
+        .with("description", format!("{description:?}"))
+        .with_files("  - src: dist/chall.zip\n  - src: solve.py\n    visible: false\n")
+}
+
+fn markdown_issues(parsed: Result<YamlShape, YamlVerifyError>) -> Vec<MarkdownIssue> {
+    part_errors(parsed)
+        .into_iter()
+        .map(|err| match err {
+            YamlAttribVerifyError::Markdown(issue) => issue,
+            err => panic!("Expected only markdown issues, but got {err:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn description_links_and_html() {
+    let chall = ChallDir::new("markdown").with_file("dist/chall.zip", "zip").with_file("solve.py", "solve");
+    let correctness = YamlCorrectness::default().with_markdown(MarkdownCorrectness::Checked {
+        allowed_html: vec!["details".into(), "summary".into()].into(),
+    });
+
+    let description = "Grab [the files](chall.zip) and [read up](https://example.com).\n\n<details><summary>Spoiler</summary>\n\nHi\n</details>";
+    assert!(YamlShape::try_from_str(&described(description).to_string(), &correctness, Some(chall.path())).is_ok());
+
+    let description = "[solver](./solve.py), [missing][ref], [xss](javascript:alert(1)) and <span onclick=\"x()\">hi</span>";
+    let issues = markdown_issues(YamlShape::try_from_str(&described(description).to_string(), &correctness, Some(chall.path())));
+    assert_eq!(issues, [
+        MarkdownIssue::UndefinedReference("ref".to_string()),
+        MarkdownIssue::MissingAttachment("solve.py".to_string()),
+        MarkdownIssue::UnsafeLink("javascript:alert(1)".to_string()),
+        MarkdownIssue::DisallowedHtml("span".to_string()),
+    ]);
+
+    // Descriptions aren't checked unless asked to be.
+    assert!(YamlShape::try_from_str(&described(description).to_string(), &YamlCorrectness::default(), Some(chall.path())).is_ok());
+}
+
+#[test]
+fn rendered_html_is_sanitized() {
+    let chall = ChallDir::new("markdown-render").with_file("dist/chall.zip", "zip").with_file("solve.py", "solve");
+    let description = "**Bold** [xss](javascript:alert(1)) <details open><script>x()</script></details>";
+    let shape = YamlShape::try_from_str(&described(description).to_string(), &YamlCorrectness::default(), Some(chall.path())).unwrap();
+
+    let markdown = MarkdownCorrectness::Checked { allowed_html: vec!["details".into()].into() };
+    let html = shape.render_description_html(&HostConfig::new("example.com"), &markdown);
+    assert_eq!(
+        html,
+        "<p><strong>Bold</strong> <a href=\"\">xss</a> <details>&lt;script&gt;x()&lt;/script&gt;</details></p>\n",
+    );
+}
+
+#[test]
+fn template_and_markdown_errors_together() {
+    let chall = ChallDir::new("markdown-template").with_file("dist/chall.zip", "zip").with_file("solve.py", "solve");
+    let correctness = YamlCorrectness::default().with_markdown(MarkdownCorrectness::Checked { allowed_html: vec![].into() });
+
+    let description = "Connect with {{ nc }} and read [the notes](notes.txt).";
+    let errors = part_errors(YamlShape::try_from_str(&described(description).to_string(), &correctness, Some(chall.path())));
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            YamlAttribVerifyError::Template(TemplateError::UndeclaredLinkTarget(DeployTargetType::Nc)),
+            YamlAttribVerifyError::Markdown(MarkdownIssue::MissingAttachment(name)),
+        ] if name == "notes.txt",
+    ));
+}
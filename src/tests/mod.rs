@@ -1,3 +1,8 @@
+pub mod chall_dir;
+pub mod deploy;
+pub mod files;
+pub mod markdown;
+pub mod paths;
 pub mod required;
 pub mod scalars;
 pub mod yaml;
@@ -0,0 +1,117 @@
+use crate::correctness::{FilePolicy, YamlCorrectness};
+use crate::description::DescriptionError;
+use crate::deploy::error::{BuildError, DeployOptionsError, DeploymentTargetOptionsError};
+use crate::files::errors::{FileErrors, FileParseErr, FilePathErr};
+use crate::flag::FlagError;
+use crate::{YamlAttribVerifyError, YamlShape};
+
+use super::chall_dir::{part_errors, ChallDir, ChallYaml};
+
+/// The shared fixture with its flag, one file and an `nc` build context at
+/// the given paths.
+fn pointing_at(flag: &str, src: &str, build: &str) -> String {
+    ChallYaml::default()
+        .with("flag", flag)
+        .with_files(format!("  - src: {src}\n"))
+        .with("deploy", format!("  nc:\n    expose: 1337/tcp\n    src: {build}\n"))
+        .to_string()
+}
+
+fn outside(shared: &ChallDir, file: &str) -> String {
+    format!("../{}/{file}", shared.path().file_name().unwrap().to_str().unwrap())
+}
+
+#[test]
+fn escaping_paths() {
+    let shared = ChallDir::new("shared").with_file("flag.txt", "bcactf{shared}").with_file("lib.so", "lib");
+    let chall = ChallDir::new("escapes").with_file("nc/Dockerfile", "FROM scratch\nEXPOSE 1337\n");
+
+    let flag = format!("{{ file: {} }}", outside(&shared, "flag.txt"));
+    let yaml = pointing_at(&flag, &outside(&shared, "lib.so"), &outside(&shared, ""));
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+
+    assert!(errors.iter().any(is_escaping_file));
+    assert!(errors.iter().any(|err| matches!(err, YamlAttribVerifyError::Flag(FlagError::EscapesRoot(_)))));
+    assert!(errors.iter().any(|err| matches!(
+        err,
+        YamlAttribVerifyError::Deploy(DeployOptionsError::Parts { nc, .. })
+            if matches!(**nc, Some(DeploymentTargetOptionsError::Parts { build: Some(BuildError::EscapesRoot(_)), .. }))
+    )));
+
+    let yaml = pointing_at(&flag, &outside(&shared, "lib.so"), "nc");
+    let correctness = YamlCorrectness::default().with_files(FilePolicy {
+        shared_roots: vec![shared.path().to_path_buf()].into(),
+        ..FilePolicy::default()
+    });
+    let shape = YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())).unwrap();
+    assert_eq!(shape.flag_str(), "bcactf{shared}");
+}
+
+fn is_escaping_file(err: &YamlAttribVerifyError) -> bool {
+    matches!(
+        err,
+        YamlAttribVerifyError::Files(FileErrors::EntryErrors(entries))
+            if matches!(entries.as_slice(), [Some(FileParseErr::Parts { path: Some(FilePathErr::EscapesRoot(_)), .. })])
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_paths() {
+    let shared = ChallDir::new("shared-links").with_file("lib.so", "lib").with_file("secret", "outside").with_file("dir/x", "x");
+    let chall = ChallDir::new("links").with_file("nc/Dockerfile", "FROM scratch\nEXPOSE 1337\n");
+    std::os::unix::fs::symlink(shared.path().join("lib.so"), chall.path().join("link.so")).unwrap();
+    std::os::unix::fs::symlink(shared.path().join("dir"), chall.path().join("link")).unwrap();
+
+    // `link/..` is the shared directory once the link is followed, not the challenge.
+    for src in ["link.so", "link/../secret"] {
+        let yaml = pointing_at("bcactf{flag}", src, "nc");
+        let errors = part_errors(YamlShape::try_from_str(&yaml, &YamlCorrectness::default(), Some(chall.path())));
+        assert!(errors.iter().any(is_escaping_file), "Expected `{src}` to escape, but got {errors:?}");
+    }
+
+    let yaml = pointing_at("bcactf{flag}", "link.so", "nc");
+    let correctness = YamlCorrectness::default().with_files(FilePolicy {
+        shared_roots: vec![shared.path().to_path_buf()].into(),
+        ..FilePolicy::default()
+    });
+    assert!(YamlShape::try_from_str(&yaml, &correctness, Some(chall.path())).is_ok());
+}
+
+#[test]
+fn description_file() {
+    let shared = ChallDir::new("shared-desc").with_file("DESCRIPTION.md", "Shared");
+    let chall = ChallDir::new("desc-file")
+        .with_file("DESCRIPTION.md", "# Heading\n\nA long description.\n")
+        .with_file("dist/chall.zip", "zip");
+    let yaml = |description: &str| ChallYaml::default()
+        .with("description", description)
+        .with_files("  - src: dist/chall.zip\n")
+        .to_string();
+
+    let shape = YamlShape::try_from_str(&yaml("{ file: DESCRIPTION.md }"), &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    assert_eq!(shape.description(), "# Heading\n\nA long description.\n");
+    assert_eq!(shape.description_path(), Some(std::path::Path::new("DESCRIPTION.md")));
+
+    let shape = YamlShape::try_from_str(&yaml("inline"), &YamlCorrectness::default(), Some(chall.path())).unwrap();
+    assert_eq!(shape.description_path(), None);
+
+    let escaping = format!("{{ file: {} }}", outside(&shared, "DESCRIPTION.md"));
+    type Check = fn(&DescriptionError) -> bool;
+    let cases: [(&str, Check); 4] = [
+        ("{ file: MISSING.md }", |err| matches!(err, DescriptionError::FileMissing(_))),
+        ("{ file: /etc/passwd }", |err| matches!(err, DescriptionError::BadPath(_))),
+        ("{ path: DESCRIPTION.md }", |err| matches!(err, DescriptionError::MappingNeedsFile)),
+        (&escaping, |err| matches!(err, DescriptionError::EscapesRoot(_))),
+    ];
+    for (description, expected) in cases {
+        let errors = part_errors(YamlShape::try_from_str(&yaml(description), &YamlCorrectness::default(), Some(chall.path())));
+        let [YamlAttribVerifyError::Description(err)] = errors.as_slice() else {
+            panic!("Expected only a description error, but got {errors:?}");
+        };
+        assert!(expected(err), "Unexpected error {err:?} for `{description}`");
+    }
+
+    let errors = part_errors(YamlShape::try_from_str(&yaml("[not, text]"), &YamlCorrectness::default(), Some(chall.path())));
+    assert!(matches!(errors.as_slice(), [YamlAttribVerifyError::DescNotString(_)]));
+}
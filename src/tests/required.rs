@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use serde_yaml::{Mapping, Value};
+use crate::correctness::{KeyRequirement, RequiredKeys, YamlCorrectness};
 use crate::{YamlShape, YamlVerifyError};
 
 #[allow(clippy::too_many_arguments)]
@@ -71,8 +72,15 @@ impl Display for MissingYaml {
     }
 }
 
+fn hints_required() -> YamlCorrectness {
+    YamlCorrectness::default().with_required(RequiredKeys { hints: KeyRequirement::Required, ..Default::default() })
+}
+
 macro_rules! test_missing {
     ($name:ident, $($fields:ident),+ $(,)?) => {
+        test_missing!($name, Default::default(); $($fields),+);
+    };
+    ($name:ident, $correctness:expr; $($fields:ident),+ $(,)?) => {
         #[test]
         fn $name() {
             #[allow(clippy::needless_update)]
@@ -83,7 +91,7 @@ macro_rules! test_missing {
             
             let parsed = YamlShape::try_from_str(
                 &yaml,
-                &Default::default(),
+                &$correctness,
                 None,
             );
     
@@ -120,7 +128,40 @@ test_missing!(missing_visible, no_visible);
 test_missing!(missing_value, no_value);
 test_missing!(missing_categories, no_categories);
 test_missing!(missing_authors, no_authors);
-test_missing!(missing_hints, no_hints);
+test_missing!(missing_hints, hints_required(); no_hints);
 
 test_missing!(missing_multiple, no_flag, no_name, no_description);
-test_missing!(missing_all, no_flag, no_name, no_description, no_visible, no_value, no_categories, no_authors, no_hints);
+test_missing!(missing_all, hints_required(); no_flag, no_name, no_description, no_visible, no_value, no_categories, no_authors, no_hints);
+
+#[test]
+fn optional_keys() {
+    let yaml = MissingYaml { no_hints: true, no_authors: true, ..Default::default() }.to_string();
+
+    let parsed = YamlShape::try_from_str(&yaml, &Default::default(), None);
+    assert!(
+        matches!(&parsed, Err(YamlVerifyError::PartErrors(errors)) if errors.len() == 1),
+        "Only the missing authors should be an error, but got {parsed:?}",
+    );
+
+    let correctness = YamlCorrectness::default().with_required(RequiredKeys {
+        authors: KeyRequirement::Optional,
+        ..Default::default()
+    });
+    let shape = YamlShape::try_from_str(&yaml, &correctness, None).unwrap();
+    assert!(shape.hints().is_empty());
+    assert!(shape.authors().is_empty());
+    assert!(shape.files().is_none());
+    assert!(shape.deploy().is_none());
+
+    let correctness = YamlCorrectness::default().with_required(RequiredKeys {
+        authors: KeyRequirement::Optional,
+        files: KeyRequirement::Required,
+        deploy: KeyRequirement::Required,
+        ..Default::default()
+    });
+    let parsed = YamlShape::try_from_str(&yaml, &correctness, None);
+    assert!(
+        matches!(&parsed, Err(YamlVerifyError::PartErrors(errors)) if errors.len() == 2),
+        "Expected the missing files and deploy to be errors, but got {parsed:?}",
+    );
+}
@@ -3,7 +3,10 @@ use std::fmt::{Display, Formatter};
 
 use serde_yaml::{Mapping, Number, Value};
 
-use crate::{YamlShape, YamlVerifyError, DEFAULT_CATEGORIES};
+use crate::{YamlShape, YamlVerifyError};
+
+use super::chall_dir::part_errors;
+
 fn generate_yaml_with_scalars(
     name: Value,
     description: Value,
@@ -218,28 +221,192 @@ macro_rules! str_list_scalar_test {
 
 #[test]
 fn categories() {
-    let categories = std::env::var("CATEGORIES").ok();
-    let categories = if let Some(category_names) = categories {
-        let cats = if &category_names == "DEFAULT" {
-            DEFAULT_CATEGORIES
-        } else {
-            &category_names
-        };
-
-        cats.split(',').map(str::to_string).collect()
-    } else {
-        vec![
-            "category1".to_string(),
-            "category2".to_string(),
-            "something-else".to_string(),
-        ]
-    };
+    let categories = vec![
+        "category1".to_string(),
+        "category2".to_string(),
+        "something-else".to_string(),
+    ];
     str_list_scalar_test! {
         base_list: categories,
         |categories| DefaultedYaml { categories, ..Default::default() }.to_string()
     }
 }
 
+#[test]
+fn defined_categories() {
+    use crate::categories::{CategoryDef, CategoryError};
+    use crate::correctness::{CategoryCorrectness, YamlCorrectness};
+    use crate::YamlAttribVerifyError;
+
+    let defs = vec![
+        CategoryDef::new("binex", "Binary Exploitation").with_aliases(vec!["pwn".into()]).with_color("#e74c3c"),
+        CategoryDef::new("webex", "Web Exploitation").with_aliases(vec!["web".into()]),
+    ];
+    let correctness = YamlCorrectness::default().with_cats(CategoryCorrectness::Defined(defs.into()));
+
+    let list = |names: &[&str]| Value::Sequence(names.iter().map(|&name| name.into()).collect());
+
+    let yaml = DefaultedYaml { categories: list(&["PWN", "web", "binex"]), ..Default::default() }.to_string();
+    let shape = YamlShape::try_from_str(&yaml, &correctness, None).unwrap();
+    assert_eq!(shape.category_str_iter().collect::<Vec<_>>(), ["binex", "webex"]);
+    assert_eq!(shape.categories()[0].display_name(), "Binary Exploitation");
+    assert_eq!(shape.categories()[0].def().and_then(|def| def.color.as_deref()), Some("#e74c3c"));
+
+    let serialized = serde_yaml::to_value(&shape).unwrap();
+    assert_eq!(serialized["categories"], list(&["binex", "webex"]));
+
+    let yaml = DefaultedYaml { categories: list(&["web", "forensics"]), ..Default::default() }.to_string();
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &correctness, None));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Categories(CategoryError::InvalidCategories(names, _))] if names == &["forensics"]
+    ));
+
+    // Each correctness carries its own categories, so the defaults can be used alongside
+    let defaults = YamlCorrectness::default().with_cats(CategoryCorrectness::defaults());
+    let shape = YamlShape::try_from_str(&yaml, &defaults, None).unwrap();
+    assert_eq!(shape.category_str_iter().collect::<Vec<_>>(), ["webex", "foren"]);
+
+    let ids: Vec<_> = crate::DEFAULT_CATEGORY_DEFS.iter().map(|def| def.id.as_ref()).collect();
+    assert_eq!(ids.join(","), crate::DEFAULT_CATEGORIES);
+}
+
+#[test]
+fn category_defs_from_yaml() {
+    use crate::categories::{parse_category_defs, CategoryDef};
+
+    let defs = parse_category_defs(concat!(
+        "- id: binex\n  name: Binary Exploitation\n  aliases: [pwn]\n  color: '#e74c3c'\n",
+        "- id: osint\n  name: OSINT\n  description: Open source intelligence\n",
+    )).unwrap();
+    assert_eq!(defs, [
+        CategoryDef::new("binex", "Binary Exploitation").with_aliases(vec!["pwn".into()]).with_color("#e74c3c"),
+        CategoryDef::new("osint", "OSINT").with_description("Open source intelligence"),
+    ]);
+
+    assert!(parse_category_defs("- id: binex\n").is_err());
+    assert!(parse_category_defs("binex: Binary Exploitation\n").is_err());
+}
+
+#[test]
+fn primary_category_and_tags() {
+    use crate::correctness::{CategoryCorrectness, TagCorrectness, YamlCorrectness};
+
+    let yaml = |extra: &str| format!("{}{extra}", DefaultedYaml {
+        categories: Value::Sequence(vec!["crypto".into(), "pwn".into()]),
+        ..Default::default()
+    });
+    let correctness = YamlCorrectness::default()
+        .with_cats(CategoryCorrectness::defaults())
+        .with_tags(TagCorrectness::List(vec!["beginner".into(), "osint".into()].into()));
+
+    let shape = YamlShape::try_from_str(&yaml("category: rev\ntags: [Beginner, osint, beginner]\n"), &correctness, None).unwrap();
+    assert_eq!(shape.primary_category().map(|cat| cat.as_str()), Some("rev"));
+    assert_eq!(shape.category_str_iter().collect::<Vec<_>>(), ["rev", "crypto", "binex"]);
+    assert_eq!(shape.tags(), ["Beginner", "osint"]);
+
+    let serialized = serde_yaml::to_value(&shape).unwrap();
+    assert_eq!(serialized["category"].as_str(), Some("rev"));
+    assert_eq!(serialized["tags"][1].as_str(), Some("osint"));
+
+    let shape = YamlShape::try_from_str(&yaml(""), &correctness, None).unwrap();
+    assert_eq!(shape.primary_category().map(|cat| cat.as_str()), Some("crypto"));
+    assert!(shape.tags().is_empty());
+
+    let parsed = YamlShape::try_from_str(&yaml("tags: [speedrun]\n"), &correctness, None);
+    assert!(matches!(parsed, Err(YamlVerifyError::Correctness(_))), "Unexpected result {parsed:?}");
+}
+
+#[test]
+fn difficulty() {
+    use crate::correctness::{DifficultyCorrectness, DifficultyLevel, YamlCorrectness};
+    use crate::YamlAttribVerifyError;
+
+    let correctness = YamlCorrectness::default().with_difficulty(DifficultyCorrectness::Levels(vec![
+        DifficultyLevel::new("easy", None, Some(150)),
+        DifficultyLevel::new("hard", Some(300), None),
+    ].into()));
+    let yaml = |points: u64, difficulty: &str| format!(
+        "{}difficulty: {difficulty}\n",
+        DefaultedYaml { value: Value::Number(points.into()), ..Default::default() },
+    );
+
+    let shape = YamlShape::try_from_str(&yaml(100, "Easy"), &correctness, None).unwrap();
+    assert_eq!(shape.difficulty(), Some("Easy"));
+    assert!(YamlShape::try_from_str(&DefaultedYaml::default().to_string(), &correctness, None).is_ok());
+
+    for (points, difficulty) in [(200, "easy"), (100, "hard"), (100, "medium")] {
+        let Err(YamlVerifyError::Correctness(issue)) = YamlShape::try_from_str(&yaml(points, difficulty), &correctness, None) else {
+            panic!("Expected {difficulty} at {points} points to be rejected");
+        };
+        assert!(issue.to_string().contains("`easy` (at most 150 points), `hard` (at least 300 points)"));
+    }
+
+    let parsed = YamlShape::try_from_str(&yaml(100, "[easy]"), &correctness, None);
+    assert!(matches!(
+        parsed,
+        Err(YamlVerifyError::PartErrors(errors)) if matches!(errors.as_slice(), [YamlAttribVerifyError::DifficultyNotString(_)])
+    ));
+}
+
+#[test]
+fn structured_hints() {
+    use crate::hints::{HintEntryError, HintError, HintUnlock, ReleaseError, RequiresError};
+    use crate::YamlAttribVerifyError;
+
+    let hints = |yaml: &str| DefaultedYaml {
+        hints: serde_yaml::from_str(yaml).unwrap(),
+        ..Default::default()
+    }.to_string();
+
+    let yaml = hints(r#"
+- free hint
+- text: look at the stack
+  cost: 25
+  requires: 0
+- text: it's a format string
+  cost: 50
+  unlock_after: 3600
+  hidden: true
+  release: 2024-06-01T12:00:00Z
+"#);
+    let shape = YamlShape::try_from_str(&yaml, &Default::default(), None).unwrap();
+    assert_eq!(shape.hint_iter().collect::<Vec<_>>(), ["free hint", "look at the stack", "it's a format string"]);
+    assert_eq!(shape.hints()[1].unlock, Some(HintUnlock::AfterHint(0)));
+    assert_eq!(shape.hints()[2].unlock, Some(HintUnlock::AfterTime(std::time::Duration::from_secs(3600))));
+
+    let serialized = serde_yaml::to_value(&shape).unwrap();
+    assert_eq!(serialized["hints"][0]["cost"].as_u64(), Some(0));
+    assert_eq!(serialized["hints"][1]["requires"].as_u64(), Some(0));
+    assert_eq!(serialized["hints"][2]["release"].as_str(), Some("2024-06-01T12:00:00Z"));
+
+    let yaml = hints(r#"
+- text: too soon
+  requires: 0
+- text: both
+  requires: 0
+  unlock_after: 60
+- text: bad release
+  release: tomorrow
+"#);
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &Default::default(), None));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Hints(HintError::Entries(entries))] if matches!(entries.as_slice(), [
+            Some(HintEntryError::Parts { requires: Some(RequiresError::NotEarlier(0)), .. }),
+            Some(HintEntryError::BothUnlocks),
+            Some(HintEntryError::Parts { release: Some(ReleaseError::BadFormat(_)), .. }),
+        ])
+    ), "Unexpected errors {errors:?}");
+
+    let yaml = hints("[{ text: a, cost: 60 }, { text: b, cost: 60 }]");
+    let errors = part_errors(YamlShape::try_from_str(&yaml, &Default::default(), None));
+    assert!(matches!(
+        errors.as_slice(),
+        [YamlAttribVerifyError::Hints(HintError::CostsExceedPoints { total: 120, points: 100 })]
+    ));
+}
+
 #[test]
 fn authors() {
     str_list_scalar_test! {
@@ -248,6 +415,42 @@ fn authors() {
     }
 }
 
+#[test]
+fn structured_authors() {
+    use crate::correctness::{AuthorCorrectness, YamlCorrectness};
+    use crate::lists::structs::{AuthorError, AuthorRole};
+    use crate::YamlAttribVerifyError;
+
+    let yaml = |authors: &str| DefaultedYaml {
+        authors: serde_yaml::from_str(authors).unwrap(),
+        ..Default::default()
+    }.to_string();
+    let authors = "  - Alice Smith\n  - name: Bob Jones\n    handle: bobj\n    email: bob@example.com\n    discord: bob.jones\n    role: tester\n";
+
+    let shape = YamlShape::try_from_str(&yaml(authors), &YamlCorrectness::default(), None).unwrap();
+    assert_eq!(shape.author_handles().collect::<Vec<_>>(), ["Alice Smith", "bobj"]);
+    assert_eq!(shape.authors()[1].role, AuthorRole::Tester);
+    assert_eq!(shape.authors()[1].email.as_deref(), Some("bob@example.com"));
+
+    let serialized = serde_yaml::to_value(&shape).unwrap();
+    assert_eq!(serialized["authors"][1]["discord"].as_str(), Some("bob.jones"));
+    assert!(serialized["authors"][0].get("email").is_none());
+
+    let bad = "  - name: Bob\n    email: not-an-email\n    role: reviewer\n";
+    let errors = part_errors(YamlShape::try_from_str(&yaml(bad), &YamlCorrectness::default(), None));
+    assert!(matches!(errors.as_slice(), [YamlAttribVerifyError::Authors(AuthorError::Entries(_))]));
+
+    let correctness = YamlCorrectness::default()
+        .with_authors(AuthorCorrectness::Registry(vec!["bobj".into(), "alice".into()].into()));
+    assert!(YamlShape::try_from_str(&yaml("  - name: Bob\n    handle: BobJ\n  - alice\n"), &correctness, None).is_ok());
+
+    let errors = part_errors(YamlShape::try_from_str(&yaml("  - name: Bob\n    handle: bobjj\n"), &correctness, None));
+    let [YamlAttribVerifyError::Authors(AuthorError::Unregistered(unknown))] = errors.as_slice() else {
+        panic!("Unexpected errors {errors:?}");
+    };
+    assert_eq!(unknown, &[("bobjj".to_string(), Some("bobj".to_string()))]);
+}
+
 #[test]
 fn hints() {
     str_list_scalar_test! {